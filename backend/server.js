@@ -44,6 +44,36 @@ const loadVerificationKey = async (circuitName) => {
 // Load verification keys
 const verificationKeys = {}
 
+// Verify-only VDF checker compiled from vdf-wasm (npm run build:vdf-wasm)
+let vdfVerifier = null
+try {
+  vdfVerifier = require('./vdf-wasm')
+} catch (error) {
+  console.warn('⚠️ [Backend] VDF verifier not built, run npm run build:vdf-wasm to check craft proofs')
+}
+
+// Recipes shared with the CLI, for the delay each craft requires
+const recipes = require('../config/recipes.json').recipes
+
+// Craft proofs are timestamped when the craft starts. Older proofs are
+// rejected, which also bounds how long accepted proofs are remembered; the
+// window leaves room for the slowest recipe on a slow machine
+const CRAFT_PROOF_MAX_AGE_SECONDS = 7 * 24 * 60 * 60
+// Tolerated clock difference for proofs dated in the future, as
+// zkgame_vdf::policy::DEFAULT_MAX_CLOCK_SKEW
+const CRAFT_PROOF_MAX_CLOCK_SKEW_SECONDS = 300
+
+// Accepted craft proofs by player, action and nonce, with their timestamps
+const acceptedCraftProofs = new Map()
+
+const forgetExpiredCraftProofs = (now) => {
+  for (const [key, timestamp] of acceptedCraftProofs) {
+    if (now - timestamp > CRAFT_PROOF_MAX_AGE_SECONDS) {
+      acceptedCraftProofs.delete(key)
+    }
+  }
+}
+
 // Load contract ABIs
 const gameCoreABI = require('../artifacts/contracts/GameCore.sol/GameCore.json').abi
 const stateManagerABI = require('../artifacts/contracts/StateManager.sol/StateManager.json').abi
@@ -240,10 +270,73 @@ app.post('/api/game/move', async (req, res) => {
 
 app.post('/api/game/craft', async (req, res) => {
   try {
-    const { playerId, recipeName, proof } = req.body
+    const { playerId, recipeName, proof, vdfOutput } = req.body
     
     console.log('Craft request:', { playerId, recipeName })
     
+    const recipe = recipes.find(r => r.name === recipeName)
+    if (!recipe) {
+      return res.status(400).json({ error: `Unknown recipe ${recipeName}` })
+    }
+    if (!/^\d+$/.test(String(playerId))) {
+      return res.status(400).json({ error: 'Invalid player id' })
+    }
+    
+    // vdfOutput is the hex encoding of VDFOutput::to_bytes
+    if (vdfOutput === undefined) {
+      return res.status(400).json({ error: 'Missing VDF proof' })
+    }
+    if (!vdfVerifier) {
+      return res.status(503).json({ error: 'VDF verifier not available' })
+    }
+    const outputBytes = Buffer.from(String(vdfOutput).replace(/^0x/, ''), 'hex')
+    const decoded = vdfVerifier.decode(outputBytes)
+    if (!decoded) {
+      return res.status(400).json({ error: 'Malformed VDF proof' })
+    }
+    const statement = {
+      playerId: decoded.playerId,
+      actionType: decoded.actionType,
+      actionId: decoded.actionId,
+      timestamp: decoded.timestamp,
+      nonce: decoded.nonce,
+      iterations: decoded.iterations,
+      iterationsPerSecond: decoded.iterationsPerSecond
+    }
+    decoded.free()
+    
+    // A valid proof only counts for the craft it was computed for, and must cover the recipe's full delay
+    if (statement.playerId !== BigInt(playerId) || statement.actionType !== 'craft' || statement.actionId !== BigInt(recipe.id)) {
+      console.log('❌ [Backend] Rejected craft with a VDF proof for another action:', { playerId, recipeName })
+      return res.status(400).json({ error: 'VDF proof belongs to another action' })
+    }
+    const requiredIterations = BigInt(recipe.required_time_seconds) * statement.iterationsPerSecond
+    if (statement.iterations < requiredIterations) {
+      console.log('❌ [Backend] Rejected craft with a VDF proof that is too short:', { playerId, recipeName })
+      return res.status(400).json({ error: `VDF proof covers ${statement.iterations} iterations, recipe requires ${requiredIterations}` })
+    }
+    const now = Math.floor(Date.now() / 1000)
+    const timestamp = Number(statement.timestamp)
+    if (timestamp > now + CRAFT_PROOF_MAX_CLOCK_SKEW_SECONDS) {
+      console.log('❌ [Backend] Rejected craft with a future-dated VDF proof:', { playerId, recipeName })
+      return res.status(400).json({ error: `VDF proof is dated ${timestamp - now} seconds in the future` })
+    }
+    if (now - timestamp > CRAFT_PROOF_MAX_AGE_SECONDS) {
+      console.log('❌ [Backend] Rejected craft with a stale VDF proof:', { playerId, recipeName })
+      return res.status(400).json({ error: `VDF proof is ${now - timestamp} seconds old, at most ${CRAFT_PROOF_MAX_AGE_SECONDS} are accepted` })
+    }
+    const proofKey = `${statement.playerId}:${statement.actionId}:${statement.nonce}`
+    if (acceptedCraftProofs.has(proofKey)) {
+      console.log('❌ [Backend] Rejected replayed VDF proof:', { playerId, recipeName })
+      return res.status(409).json({ error: 'VDF proof was already used' })
+    }
+    if (!vdfVerifier.verify(outputBytes)) {
+      console.log('❌ [Backend] Rejected craft with invalid VDF proof:', { playerId, recipeName })
+      return res.status(400).json({ error: 'Invalid VDF proof' })
+    }
+    forgetExpiredCraftProofs(now)
+    acceptedCraftProofs.set(proofKey, timestamp)
+    
     // In a real implementation, you would verify the proof and submit to contract
     res.json({ success: true, craftId: `craft_${playerId}_${Date.now()}` })
   } catch (error) {
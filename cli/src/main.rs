@@ -2,8 +2,39 @@ use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use zkgame_vdf::VDFInput;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use zkgame_vdf::calibration::DEFAULT_CALIBRATION_ITERATIONS;
+use zkgame_vdf::policy::input_anchor;
+use zkgame_vdf::{
+    CalibrationProfile, CancellationToken, ComputeStatus, ParameterSet, PartialComputation, SchemeKind, VDFEngine,
+    VDFInput, VDFOutput, VdfError, Verdict, VerificationPolicy,
+};
+
+/// Seconds between lock file refreshes of a running worker
+const WORKER_HEARTBEAT_SECONDS: u64 = 30;
+
+/// A worker lock not refreshed for this long belongs to a worker that died
+const WORKER_STALE_SECONDS: u64 = 600;
+
+/// A state lock held this long belongs to a command that died
+const STATE_LOCK_STALE_SECONDS: u64 = 30;
+
+/// Pause between attempts to take the state lock
+const STATE_LOCK_RETRY_MILLIS: u64 = 50;
+
+/// Craft status while its VDF proof is outstanding
+const CRAFT_COMPUTING: &str = "computing";
+
+/// Craft status once the worker has stored the VDF proof
+const CRAFT_READY: &str = "ready";
+
+/// Craft status after the worker failed to compute its proof
+const CRAFT_FAILED: &str = "failed";
 
 /// ZKGame CLI - Zero-knowledge proof-based simulation game
 #[derive(Parser)]
@@ -40,6 +71,13 @@ enum Commands {
         #[arg(short, long)]
         craft_id: String,
     },
+    /// Queue a failed craft for the background worker again
+    ///
+    /// The computation resumes from its snapshot unless the snapshot is invalid.
+    RetryCraft {
+        #[arg(short, long)]
+        craft_id: String,
+    },
     /// Trade with a store
     Trade {
         #[arg(short, long)]
@@ -74,6 +112,69 @@ enum Commands {
     },
     /// Show player status
     Status,
+    /// Compute VDF proofs for pending crafts until none are left
+    ///
+    /// `craft` starts a worker in the background; run this directly to
+    /// watch it or after a worker was stopped.
+    Worker,
+    /// Compute, verify and inspect VDF proofs outside the game flow
+    Vdf {
+        #[command(subcommand)]
+        command: VdfCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum VdfCommands {
+    /// Compute a VDF output and write it as JSON
+    Compute {
+        /// Number of squarings
+        #[arg(short, long, required_unless_present_any = ["seconds", "resume"], conflicts_with = "seconds")]
+        iterations: Option<u64>,
+        /// Nominal duration in seconds, converted at the protocol rate
+        #[arg(short, long)]
+        seconds: Option<u64>,
+        /// Action type bound into the proof, defaults to debug
+        #[arg(short, long)]
+        action_type: Option<String>,
+        /// Proof scheme (wesolowski or pietrzak)
+        #[arg(long, default_value = "wesolowski")]
+        scheme: String,
+        /// Output file, defaults to proofs/vdf_<player_id>_<timestamp>.json
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Continue the computation saved in this snapshot instead of starting a new one
+        ///
+        /// Iterations, seconds or an action type given as well must match the snapshot.
+        #[arg(long)]
+        resume: Option<PathBuf>,
+    },
+    /// Verify a VDF output file and print the verdict
+    Verify {
+        /// VDF output as JSON or in the binary encoding
+        file: PathBuf,
+        /// Reject outputs whose delay ended more than this many seconds ago
+        #[arg(long)]
+        max_age: Option<u64>,
+    },
+    /// Print the decoded fields of a VDF output file
+    Inspect {
+        /// VDF output as JSON or in the binary encoding
+        file: PathBuf,
+    },
+    /// Measure this machine's squaring speed and save the calibration profile
+    Calibrate {
+        #[arg(short, long, default_value_t = DEFAULT_CALIBRATION_ITERATIONS)]
+        iterations: u64,
+    },
+    /// Measure squaring, proving and verification throughput
+    Bench {
+        #[arg(short, long, default_value = "100000")]
+        iterations: u64,
+        /// Prover threads
+        #[arg(short, long, default_value = "1")]
+        threads: usize,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -108,6 +209,8 @@ struct CraftInProgress {
     required_time: u64,
     vdf_input: VDFInput,
     status: String,
+    #[serde(default)]
+    vdf_output: Option<VDFOutput>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -136,6 +239,9 @@ fn main() {
         Commands::CompleteCraft { craft_id } => {
             complete_craft(&craft_id);
         }
+        Commands::RetryCraft { craft_id } => {
+            retry_craft(&craft_id);
+        }
         Commands::Trade { store_id, action, item, quantity } => {
             trade_with_store(store_id, &action, &item, quantity);
         }
@@ -151,10 +257,31 @@ fn main() {
         Commands::Status => {
             show_status();
         }
+        Commands::Worker => {
+            run_worker();
+        }
+        Commands::Vdf { command } => match command {
+            VdfCommands::Compute { iterations, seconds, action_type, scheme, output, resume } => {
+                vdf_compute(iterations, seconds, action_type.as_deref(), &scheme, output, resume);
+            }
+            VdfCommands::Verify { file, max_age } => {
+                vdf_verify(&file, max_age);
+            }
+            VdfCommands::Inspect { file } => {
+                vdf_inspect(&file);
+            }
+            VdfCommands::Calibrate { iterations } => {
+                vdf_calibrate(iterations);
+            }
+            VdfCommands::Bench { iterations, threads } => {
+                vdf_bench(iterations, threads);
+            }
+        },
     }
 }
 
 fn init_player(name: &str) {
+    let _lock = StateLock::acquire();
     println!("Initializing player: {}", name);
     
     // Generate player ID (in production, use proper key generation)
@@ -203,6 +330,7 @@ fn init_player(name: &str) {
 }
 
 fn move_player(x: i32, y: i32) {
+    let _lock = StateLock::acquire();
     let mut config = load_config();
     
     if let Some(ref mut state) = config.player_state {
@@ -250,6 +378,7 @@ fn move_player(x: i32, y: i32) {
 }
 
 fn claim_rewards() {
+    let _lock = StateLock::acquire();
     let mut config = load_config();
     
     if let Some(ref mut state) = config.player_state {
@@ -285,6 +414,7 @@ fn claim_rewards() {
 }
 
 fn start_craft(recipe_name: &str) {
+    let _lock = StateLock::acquire();
     let mut config = load_config();
     
     if let Some(ref state) = config.player_state {
@@ -313,7 +443,7 @@ fn start_craft(recipe_name: &str) {
             random_salt: generate_random_salt(),
         };
         
-        // Start VDF computation in background
+        // Queue the VDF computation for the background worker
         let craft_id = format!("craft_{}_{}", state.player_id, get_current_timestamp());
         let craft = CraftInProgress {
             craft_id: craft_id.clone(),
@@ -321,7 +451,8 @@ fn start_craft(recipe_name: &str) {
             start_time: get_current_timestamp(),
             required_time: recipe.required_time_seconds,
             vdf_input,
-            status: "computing".to_string(),
+            status: CRAFT_COMPUTING.to_string(),
+            vdf_output: None,
         };
         
         config.active_crafts.push(craft);
@@ -330,28 +461,41 @@ fn start_craft(recipe_name: &str) {
         println!("Started crafting '{}'", recipe_name);
         println!("Craft ID: {}", craft_id);
         println!("Estimated completion time: {} seconds", recipe.required_time_seconds);
-        println!("VDF computation started in background...");
+        let engine = load_engine();
+        let iterations = engine.time_to_iterations(recipe.required_time_seconds);
+        if let Some(local) = engine.estimated_local_time(iterations) {
+            println!("Expected VDF time on this machine: {:.0} seconds ({} iterations)", local, iterations);
+        }
+        if let Some(mismatch) = engine.timing_mismatch(iterations) {
+            println!("Warning: {}", mismatch);
+        }
+        if spawn_worker() {
+            println!("VDF computation started in background (log: {})", get_worker_log_path().display());
+        } else {
+            println!("Warning: Could not start the background worker, run 'zkgame worker' to compute the VDF proof");
+        }
     } else {
         println!("Error: Player not initialized");
     }
 }
 
 fn complete_craft(craft_id: &str) {
+    let _lock = StateLock::acquire();
     let mut config = load_config();
     
     if let Some(craft_index) = config.active_crafts.iter().position(|c| c.craft_id == craft_id) {
         // Clone the craft data before removing it
         let craft = config.active_crafts[craft_index].clone();
         
-        // Check if enough time has passed
-        let current_time = get_current_timestamp();
-        if current_time - craft.start_time < craft.required_time {
-            println!("Error: Craft not yet complete. {} seconds remaining", 
-                craft.required_time - (current_time - craft.start_time));
+        // The craft completes once the worker has stored its VDF proof
+        if craft.vdf_output.is_none() {
+            println!("Error: Craft not yet complete, VDF proof is {}", describe_craft(&craft));
+            return;
+        }
+        if let Err(e) = check_craft_proof(&craft) {
+            println!("Error: {}", e);
             return;
         }
-        
-        // In production, verify VDF proof and generate craft proof
         println!("VDF proof verified, generating craft proof...");
         
         // Update player state
@@ -374,6 +518,77 @@ fn complete_craft(craft_id: &str) {
     }
 }
 
+/// Check the stored VDF proof of `craft` before the craft completes
+///
+/// Only proofs of the current parameter set count, and they must cover the
+/// full delay of the recipe.
+fn check_craft_proof(craft: &CraftInProgress) -> Result<(), String> {
+    let output = craft.vdf_output.as_ref()
+        .ok_or_else(|| format!("Craft '{}' has no VDF proof yet", craft.craft_id))?;
+    if output.input != craft.vdf_input {
+        return Err(format!("VDF proof does not belong to craft '{}'", craft.craft_id));
+    }
+    
+    ParameterSet::current().check_output(output)
+        .map_err(|e| format!("VDF proof for craft '{}' was not made with the current parameters: {}", craft.craft_id, e))?;
+    let engine = VDFEngine::from_parameters(&output.parameters)
+        .map_err(|e| format!("Failed to verify VDF proof: {}", e))?;
+    let required_iterations = engine.time_to_iterations(craft.required_time);
+    if output.iterations < required_iterations {
+        return Err(format!("VDF proof covers {} iterations, craft '{}' requires {}",
+            output.iterations, craft.craft_id, required_iterations));
+    }
+    match engine.verify_output(output) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("VDF proof for craft '{}' is invalid", craft.craft_id)),
+        Err(e) => Err(format!("Failed to verify VDF proof: {}", e)),
+    }
+}
+
+fn retry_craft(craft_id: &str) {
+    let _lock = StateLock::acquire();
+    let mut config = load_config();
+    
+    if let Err(e) = requeue_craft(&mut config, craft_id, &load_engine(), &get_snapshot_path(craft_id)) {
+        println!("Error: {}", e);
+        return;
+    }
+    save_config(&config);
+    
+    if spawn_worker() {
+        println!("Craft '{}' queued again (log: {})", craft_id, get_worker_log_path().display());
+    } else {
+        println!("Warning: Could not start the background worker, run 'zkgame worker' to compute the VDF proof");
+    }
+}
+
+/// Mark the failed craft `craft_id` as computing again
+///
+/// The snapshot at `snapshot_path` is kept so the worker resumes from it,
+/// unless it no longer loads; then it is removed and the computation starts
+/// over.
+fn requeue_craft(config: &mut GameConfig, craft_id: &str, engine: &VDFEngine, snapshot_path: &Path) -> Result<(), String> {
+    let craft = config.active_crafts.iter_mut().find(|craft| craft.craft_id == craft_id)
+        .ok_or_else(|| format!("Craft '{}' not found", craft_id))?;
+    if craft.status != CRAFT_FAILED {
+        return Err(format!("Craft '{}' has not failed, VDF proof is {}", craft_id, describe_craft(craft)));
+    }
+    
+    // Keep the progress unless the snapshot itself is what failed
+    if snapshot_path.exists() {
+        match engine.load_snapshot(snapshot_path) {
+            Ok(partial) => println!("Resuming from {} of {} iterations", partial.completed(), partial.iterations()),
+            Err(e) => {
+                println!("Warning: Discarding invalid snapshot ({}), the computation starts over", e);
+                fs::remove_file(snapshot_path).unwrap_or_default();
+            }
+        }
+    }
+    
+    craft.status = CRAFT_COMPUTING.to_string();
+    Ok(())
+}
+
 fn trade_with_store(store_id: u64, action: &str, item: &str, quantity: u32) {
     println!("Trading with store {}: {} {} {} units", store_id, action, quantity, item);
     
@@ -383,6 +598,7 @@ fn trade_with_store(store_id: u64, action: &str, item: &str, quantity: u32) {
 }
 
 fn buy_store(_city: &str, price: u64) {
+    let _lock = StateLock::acquire();
     let mut config = load_config();
     
     if let Some(ref mut state) = config.player_state {
@@ -454,14 +670,7 @@ fn show_status() {
         if !config.active_crafts.is_empty() {
             println!("\n=== Active Crafts ===");
             for craft in &config.active_crafts {
-                let elapsed = get_current_timestamp() - craft.start_time;
-                let remaining = if elapsed < craft.required_time {
-                    craft.required_time - elapsed
-                } else {
-                    0
-                };
-                println!("- {} (ID: {}) - {} seconds remaining", 
-                    craft.recipe_name, craft.craft_id, remaining);
+                println!("- {} (ID: {}) - {}", craft.recipe_name, craft.craft_id, describe_craft(craft));
             }
         }
     } else {
@@ -469,6 +678,492 @@ fn show_status() {
     }
 }
 
+// Background VDF worker
+
+fn run_worker() {
+    if !acquire_worker_lock() {
+        println!("Another worker is already running");
+        return;
+    }
+    
+    loop {
+        while let Some(craft) = next_pending_craft() {
+            compute_craft(&craft);
+        }
+        release_worker_lock();
+        
+        // A craft queued while the lock was being released did not start a
+        // worker of its own, so look once more before exiting
+        if next_pending_craft().is_none() || !acquire_worker_lock() {
+            break;
+        }
+    }
+    println!("No pending crafts left, worker exiting");
+}
+
+fn next_pending_craft() -> Option<CraftInProgress> {
+    load_config().active_crafts.into_iter()
+        .find(|craft| craft.status == CRAFT_COMPUTING && craft.vdf_output.is_none())
+}
+
+/// Compute the VDF proof of `craft`, resuming from its snapshot, and store it in the state file
+fn compute_craft(craft: &CraftInProgress) {
+    let engine = load_engine();
+    let iterations = engine.time_to_iterations(craft.required_time);
+    let snapshot_path = get_snapshot_path(&craft.craft_id);
+    if let Some(parent) = snapshot_path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    
+    println!("Computing {} iterations for craft '{}'", iterations, craft.craft_id);
+    let result = with_heartbeat(&get_worker_lock_path(), Duration::from_secs(WORKER_HEARTBEAT_SECONDS), || {
+        engine.compute_persistent(&craft.vdf_input, iterations, &snapshot_path, |_| {}, &CancellationToken::new())
+    });
+    
+    // Reload under the state lock so that changes made while computing are kept
+    let _lock = StateLock::acquire();
+    let mut config = load_config();
+    let entry = match config.active_crafts.iter_mut().find(|entry| entry.craft_id == craft.craft_id) {
+        Some(entry) => entry,
+        None => {
+            println!("Craft '{}' was removed while computing", craft.craft_id);
+            return;
+        }
+    };
+    match result {
+        Ok(ComputeStatus::Complete(output)) => {
+            println!("Craft '{}' is ready ({:.0} seconds)", craft.craft_id, output.computation_time);
+            entry.vdf_output = Some(output);
+            entry.status = CRAFT_READY.to_string();
+        }
+        Ok(ComputeStatus::Cancelled(_)) => {
+            println!("Error: Computation for craft '{}' was cancelled", craft.craft_id);
+            entry.status = CRAFT_FAILED.to_string();
+        }
+        Err(e) => {
+            println!("Error: VDF computation for craft '{}' failed: {}", craft.craft_id, e);
+            entry.status = CRAFT_FAILED.to_string();
+        }
+    }
+    save_config(&config);
+}
+
+/// Run `work` while a timer thread rewrites the lock file at `lock_path`
+/// every `interval`
+///
+/// The timer keeps the lock fresh while no progress is reported, as during
+/// proof generation after the last squaring, so other commands do not take
+/// the worker for dead.
+fn with_heartbeat<T>(lock_path: &Path, interval: Duration, work: impl FnOnce() -> T) -> T {
+    let (stop, stopped) = mpsc::channel::<()>();
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                fs::write(lock_path, std::process::id().to_string()).unwrap_or_default();
+            }
+        });
+        let result = work();
+        drop(stop);
+        result
+    })
+}
+
+/// Start a detached `zkgame worker` unless a live one holds the lock
+fn spawn_worker() -> bool {
+    let lock_path = get_worker_lock_path();
+    if lock_path.exists() && !lock_is_stale(&lock_path, WORKER_STALE_SECONDS) {
+        return true;
+    }
+    
+    let log_path = get_worker_log_path();
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let spawned = (|| -> std::io::Result<()> {
+        let log = OpenOptions::new().create(true).append(true).open(&log_path)?;
+        Command::new(std::env::current_exe()?)
+            .arg("worker")
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()?;
+        Ok(())
+    })();
+    spawned.is_ok()
+}
+
+fn acquire_worker_lock() -> bool {
+    acquire_worker_lock_at(&get_worker_lock_path())
+}
+
+/// Take the worker lock at `path`, replacing a lock whose worker stopped refreshing it
+fn acquire_worker_lock_at(path: &Path) -> bool {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    if lock_is_stale(path, WORKER_STALE_SECONDS) {
+        fs::remove_file(path).unwrap_or_default();
+    }
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(mut file) => {
+            write!(file, "{}", std::process::id()).unwrap_or_default();
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+fn release_worker_lock() {
+    fs::remove_file(get_worker_lock_path()).unwrap_or_default();
+}
+
+/// Whether the lock file at `path` was last written more than `stale_seconds` ago
+fn lock_is_stale(path: &Path, stale_seconds: u64) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age.as_secs() > stale_seconds)
+}
+
+/// Exclusive access to the state file, released when dropped
+///
+/// Every command that reloads, changes and saves the state file holds it
+/// across all three steps, and so does the worker when it stores a proof.
+/// Otherwise one process could save over a change another made in between.
+struct StateLock {
+    path: PathBuf,
+}
+
+impl StateLock {
+    /// Wait until no other process holds the lock, then take it
+    fn acquire() -> Self {
+        Self::acquire_at(get_state_lock_path())
+    }
+    
+    /// `acquire` with the lock file at `path`
+    fn acquire_at(path: PathBuf) -> Self {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id()).unwrap_or_default();
+                    return Self { path };
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if lock_is_stale(&path, STATE_LOCK_STALE_SECONDS) {
+                        fs::remove_file(&path).unwrap_or_default();
+                    } else {
+                        std::thread::sleep(std::time::Duration::from_millis(STATE_LOCK_RETRY_MILLIS));
+                    }
+                }
+                Err(e) => panic!("Failed to lock {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        fs::remove_file(&self.path).unwrap_or_default();
+    }
+}
+
+/// Proof status of a craft for `status` and `complete-craft`
+fn describe_craft(craft: &CraftInProgress) -> String {
+    match craft.status.as_str() {
+        CRAFT_COMPUTING => {
+            let partial = PartialComputation::load(&get_snapshot_path(&craft.craft_id)).ok();
+            match partial.filter(|partial| partial.iterations() > 0) {
+                Some(partial) => format!("computing, {}% done", partial.completed() * 100 / partial.iterations()),
+                None => "computing".to_string(),
+            }
+        }
+        CRAFT_FAILED => format!("failed, see the worker log and run 'zkgame retry-craft --craft-id {}'", craft.craft_id),
+        status => status.to_string(),
+    }
+}
+
+// VDF debugging commands
+
+fn vdf_compute(iterations: Option<u64>, seconds: Option<u64>, action_type: Option<&str>, scheme: &str, output_path: Option<PathBuf>, resume: Option<PathBuf>) {
+    let scheme = match scheme {
+        "wesolowski" => SchemeKind::Wesolowski,
+        "pietrzak" => SchemeKind::Pietrzak,
+        _ => {
+            println!("Error: Unknown scheme '{}', expected wesolowski or pietrzak", scheme);
+            return;
+        }
+    };
+    let engine = load_engine().with_scheme(scheme);
+    let requested_iterations = iterations.or_else(|| seconds.map(|seconds| engine.time_to_iterations(seconds)));
+    
+    let (input, iterations) = match &resume {
+        Some(snapshot_path) => match resumed_computation(&engine, snapshot_path, requested_iterations, action_type) {
+            Ok(computation) => computation,
+            Err(e) => {
+                println!("Error: {}", e);
+                return;
+            }
+        },
+        None => {
+            // Bind the proof to the local player if there is one
+            let config = load_config();
+            let input = VDFInput {
+                player_id: config.player_state.as_ref().map_or(0, |state| state.player_id),
+                action_type: action_type.unwrap_or("debug").to_string(),
+                action_id: 0,
+                timestamp: get_current_timestamp(),
+                nonce: 0,
+                random_salt: generate_random_salt(),
+            };
+            (input, requested_iterations.unwrap_or(0))
+        }
+    };
+    
+    let path = output_path.unwrap_or_else(|| PathBuf::from(format!("proofs/vdf_{}_{}.json", input.player_id, input.timestamp)));
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let snapshot_path = resume.unwrap_or_else(|| path.with_extension("snapshot"));
+    
+    // Ctrl-C stops the squaring; the snapshot keeps the progress
+    let cancel = CancellationToken::new();
+    let handler_token = cancel.clone();
+    if let Err(e) = ctrlc::set_handler(move || handler_token.cancel()) {
+        println!("Warning: Could not install the Ctrl-C handler: {}", e);
+    }
+    
+    println!("Computing {} iterations ({} nominal seconds)", iterations, engine.iterations_to_time(iterations));
+    if let Some(local) = engine.estimated_local_time(iterations) {
+        println!("Expected time on this machine: {:.0} seconds", local);
+    }
+    let mut last_percent = None;
+    let status = engine.compute_persistent(&input, iterations, &snapshot_path, |progress| {
+        let percent = (progress.fraction() * 100.0) as u32;
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            print!("\r{:3}% ({} / {} iterations, ETA {:.0}s)   ", percent, progress.iterations_done, progress.total_iterations, progress.eta_seconds);
+            std::io::stdout().flush().unwrap_or_default();
+        }
+    }, &cancel);
+    println!();
+    
+    let output = match status {
+        Ok(ComputeStatus::Complete(output)) => output,
+        Ok(ComputeStatus::Cancelled(partial)) => {
+            println!("Stopped after {} of {} iterations, progress saved to {}", partial.completed(), partial.iterations(), snapshot_path.display());
+            println!("Run 'zkgame vdf compute --resume {} --output {}' to continue", snapshot_path.display(), path.display());
+            return;
+        }
+        Err(e) => {
+            println!("Error: VDF computation failed: {}", e);
+            return;
+        }
+    };
+    
+    fs::write(&path, serde_json::to_string_pretty(&output).unwrap()).unwrap();
+    println!("VDF output written to {} ({:.2} seconds)", path.display(), output.computation_time);
+}
+
+/// Input and iterations of the computation saved at `snapshot_path`
+///
+/// Iterations or an action type given on the command line must match the
+/// snapshot, so `--resume` never continues a different computation than the
+/// one asked for.
+fn resumed_computation(engine: &VDFEngine, snapshot_path: &Path, iterations: Option<u64>, action_type: Option<&str>) -> Result<(VDFInput, u64), String> {
+    let partial = engine.load_snapshot(snapshot_path)
+        .map_err(|e| format!("Failed to load snapshot {}: {}", snapshot_path.display(), e))?;
+    if let Some(iterations) = iterations.filter(|&iterations| iterations != partial.iterations()) {
+        return Err(format!("Snapshot {} is for {} iterations, not {}", snapshot_path.display(), partial.iterations(), iterations));
+    }
+    if let Some(action_type) = action_type.filter(|&action_type| action_type != partial.input().action_type) {
+        return Err(format!("Snapshot {} is for action type '{}', not '{}'", snapshot_path.display(), partial.input().action_type, action_type));
+    }
+    Ok((partial.input().clone(), partial.iterations()))
+}
+
+fn vdf_verify(path: &Path, max_age: Option<u64>) {
+    let output = match load_vdf_output(path) {
+        Ok(output) => output,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+    
+    let mut policy = VerificationPolicy::new();
+    if let Some(max_age) = max_age {
+        policy = policy.with_max_age(max_age);
+    }
+    
+    // Check the output under the parameter set it records
+    let start = Instant::now();
+    let verdict = VDFEngine::from_parameters(&output.parameters)
+        .and_then(|engine| engine.verify_with_policy(&output, &policy));
+    match verdict {
+        Ok(verdict) => {
+            println!("Verdict: {}", describe_verdict(&verdict));
+            println!("Parameter set: {}", output.parameters.name);
+            println!("Checked in {:.1} ms", start.elapsed().as_secs_f64() * 1000.0);
+        }
+        Err(e) => println!("Error: Failed to verify VDF output: {}", e),
+    }
+}
+
+fn vdf_inspect(path: &Path) {
+    let output = match load_vdf_output(path) {
+        Ok(output) => output,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+    let parameters = &output.parameters;
+    let input = &output.input;
+    
+    println!("=== Parameters ===");
+    println!("Set: {}", parameters.name);
+    println!("Group: {}", parameters.modulus_id);
+    println!("Hash-to-prime version: {}", parameters.hash_to_prime_version);
+    println!("Proof version: {}", parameters.proof_version);
+    println!("Security: {} bits", parameters.security_bits);
+    println!("Iterations per second: {}", parameters.iterations_per_second);
+    
+    println!("\n=== Input ===");
+    println!("Player ID: {}", input.player_id);
+    println!("Action type: {}", input.action_type);
+    if let Some(anchor) = input_anchor(input) {
+        println!("Anchor: {:?}", anchor);
+    }
+    println!("Action ID: {}", input.action_id);
+    println!("Timestamp: {}", input.timestamp);
+    println!("Nonce: {}", input.nonce);
+    println!("Random salt: {}", input.random_salt);
+    
+    println!("\n=== Output ===");
+    println!("Scheme: {:?}", output.scheme);
+    let rate = parameters.iterations_per_second.max(1);
+    println!("Iterations: {} ({} nominal seconds)", output.iterations, output.iterations / rate);
+    println!("Computation time: {:.2} seconds", output.computation_time);
+    println!("Output: {}", abbreviate_hex(&output.output));
+    let width = zkgame_vdf::ELEMENT_BYTES;
+    println!("Proof: {} bytes, {} element(s)", output.proof.len(), output.proof.len() / width);
+    for (index, element) in output.proof.chunks(width).enumerate() {
+        println!("  [{}] {}", index, abbreviate_hex(element));
+    }
+    println!("Binary encoding: {} bytes", output.to_bytes().len());
+}
+
+fn vdf_calibrate(iterations: u64) {
+    println!("Timing {} squarings...", iterations);
+    let profile = match CalibrationProfile::measure(&VDFEngine::new(), iterations) {
+        Ok(profile) => profile,
+        Err(e) => {
+            println!("Error: Calibration failed: {}", e);
+            return;
+        }
+    };
+    
+    let path = get_calibration_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    if let Err(e) = profile.save(&path) {
+        println!("Error: Failed to save calibration: {}", e);
+        return;
+    }
+    
+    println!("Measured {:.0} iterations per second", profile.iterations_per_second);
+    println!("Calibration saved to {}", path.display());
+    let engine = VDFEngine::new().with_calibration(profile);
+    let one_hour = engine.time_to_iterations(3600);
+    if let Some(local) = engine.estimated_local_time(one_hour) {
+        println!("A one-hour action takes about {:.0} seconds on this machine", local);
+    }
+    if let Some(mismatch) = engine.timing_mismatch(one_hour) {
+        println!("Warning: {}", mismatch);
+    }
+}
+
+fn vdf_bench(iterations: u64, threads: usize) {
+    let engine = VDFEngine::new().with_prover_threads(threads);
+    let input = VDFInput {
+        player_id: 0,
+        action_type: "bench".to_string(),
+        action_id: 0,
+        timestamp: get_current_timestamp(),
+        nonce: 0,
+        random_salt: generate_random_salt(),
+    };
+    
+    println!("Benchmarking {} iterations with {} prover thread(s)...", iterations, engine.prover_threads());
+    let start = Instant::now();
+    let mut squared = None;
+    let status = engine.compute_with_progress(&input, iterations, |progress| {
+        if progress.iterations_done == progress.total_iterations {
+            squared = Some(Instant::now());
+        }
+    }, &CancellationToken::new());
+    let output = match status {
+        Ok(ComputeStatus::Complete(output)) => output,
+        Ok(ComputeStatus::Cancelled(_)) => {
+            println!("Error: VDF computation was cancelled");
+            return;
+        }
+        Err(e) => {
+            println!("Error: VDF computation failed: {}", e);
+            return;
+        }
+    };
+    let finished = Instant::now();
+    let squared = squared.unwrap_or(finished);
+    
+    let verify_start = Instant::now();
+    let valid = matches!(engine.verify_output(&output), Ok(true));
+    let verify_time = verify_start.elapsed();
+    
+    let squaring_seconds = (squared - start).as_secs_f64();
+    let rate = iterations as f64 / squaring_seconds.max(f64::EPSILON);
+    println!("Squaring: {:.2} s ({:.0} iterations per second, {:.1}x the protocol rate)",
+        squaring_seconds, rate, rate / engine.parameters().iterations_per_second as f64);
+    println!("Proving: {:.2} s", (finished - squared).as_secs_f64());
+    println!("Verification: {:.1} ms ({})", verify_time.as_secs_f64() * 1000.0, if valid { "valid" } else { "INVALID" });
+    println!("Prover memory: {} KiB", engine.memory_usage(iterations) / 1024);
+}
+
+/// Read a `VDFOutput` written as JSON or in the binary encoding
+fn load_vdf_output(path: &Path) -> Result<VDFOutput, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    match serde_json::from_slice(&bytes) {
+        Ok(output) => Ok(output),
+        Err(json_error) => VDFOutput::from_bytes(&bytes)
+            .map_err(|e| format!("{} is not a VDF output (JSON: {}; binary: {})", path.display(), json_error, e)),
+    }
+}
+
+fn describe_verdict(verdict: &Verdict) -> String {
+    match verdict {
+        Verdict::Valid => "valid".to_string(),
+        Verdict::InvalidProof => "invalid proof".to_string(),
+        Verdict::Stale { age, max_age } => format!("stale, the delay ended {} seconds ago (limit {})", age, max_age),
+        Verdict::FutureDated { ahead, max_clock_skew } => {
+            format!("future-dated, {} seconds ahead of this clock (tolerance {})", ahead, max_clock_skew)
+        }
+        Verdict::WrongAnchor { found } => format!("wrong anchor ({:?})", found),
+    }
+}
+
+/// `0x` and the first and last 8 bytes of `bytes`
+fn abbreviate_hex(bytes: &[u8]) -> String {
+    if bytes.len() <= 16 {
+        return format!("0x{}", hex::encode(bytes));
+    }
+    format!("0x{}...{} ({} bytes)", hex::encode(&bytes[..8]), hex::encode(&bytes[bytes.len() - 8..]), bytes.len())
+}
+
 // Helper functions
 
 fn generate_player_id() -> u64 {
@@ -495,6 +1190,39 @@ fn get_config_path() -> PathBuf {
     dirs::home_dir().unwrap().join(".zkgame").join("config.json")
 }
 
+fn get_calibration_path() -> PathBuf {
+    dirs::home_dir().unwrap().join(".zkgame").join("calibration.json")
+}
+
+fn get_state_lock_path() -> PathBuf {
+    dirs::home_dir().unwrap().join(".zkgame").join("state.lock")
+}
+
+fn get_worker_lock_path() -> PathBuf {
+    dirs::home_dir().unwrap().join(".zkgame").join("worker.lock")
+}
+
+fn get_worker_log_path() -> PathBuf {
+    dirs::home_dir().unwrap().join(".zkgame").join("worker.log")
+}
+
+/// Snapshot of a craft's VDF computation, see `VDFEngine::compute_persistent`
+fn get_snapshot_path(craft_id: &str) -> PathBuf {
+    dirs::home_dir().unwrap().join(".zkgame").join("snapshots").join(format!("{}.snapshot", craft_id))
+}
+
+/// VDF engine with this machine's calibration profile, if one was saved
+fn load_engine() -> VDFEngine {
+    match CalibrationProfile::load(&get_calibration_path()) {
+        Ok(profile) => VDFEngine::new().with_calibration(profile),
+        Err(VdfError::InvalidCalibration(reason)) => {
+            println!("Warning: Ignoring calibration profile ({}), run 'zkgame vdf calibrate' again", reason);
+            VDFEngine::new()
+        }
+        Err(_) => VDFEngine::new(),
+    }
+}
+
 fn load_config() -> GameConfig {
     let path = get_config_path();
     if path.exists() {
@@ -519,7 +1247,11 @@ fn save_config(config: &GameConfig) {
         fs::create_dir_all(parent).unwrap();
     }
     let content = serde_json::to_string_pretty(config).unwrap();
-    fs::write(&path, content).unwrap();
+    
+    // Replace the file atomically so readers without the state lock never see half of it
+    let temporary = path.with_extension("json.tmp");
+    fs::write(&temporary, content).unwrap();
+    fs::rename(&temporary, &path).unwrap();
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -654,6 +1386,7 @@ fn add_item_to_inventory(state: &mut PlayerState, item_type: &str, quantity: u32
 }
 
 fn gather_resources(resource_type: &str, quantity: u32) {
+    let _lock = StateLock::acquire();
     let mut config = load_config();
     
     if let Some(ref mut state) = config.player_state {
@@ -788,3 +1521,266 @@ fn submit_resource_gathering_proof(_proof_path: &str) -> String {
     println!("📤 Submitting resource gathering proof to GameCore.gatherResources()...");
     tx_hash
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+    use zkgame_vdf::PROGRESS_STEP;
+
+    /// Move the modification time of `path` `seconds` into the past
+    fn backdate(path: &Path, seconds: u64) {
+        let file = OpenOptions::new().write(true).open(path).expect("Failed to open lock file");
+        file.set_modified(SystemTime::now() - Duration::from_secs(seconds)).expect("Failed to backdate lock file");
+    }
+
+    fn craft_input() -> VDFInput {
+        VDFInput {
+            player_id: 42,
+            action_type: "craft".to_string(),
+            action_id: 5,
+            timestamp: 1234567890,
+            nonce: 3,
+            random_salt: 777,
+        }
+    }
+
+    fn craft(status: &str, required_time: u64, vdf_output: Option<VDFOutput>) -> CraftInProgress {
+        CraftInProgress {
+            craft_id: "craft_42_1".to_string(),
+            recipe_name: "basic_tool".to_string(),
+            start_time: 1234567890,
+            required_time,
+            vdf_input: craft_input(),
+            status: status.to_string(),
+            vdf_output,
+        }
+    }
+
+    fn config_with(craft: CraftInProgress) -> GameConfig {
+        GameConfig {
+            player_state: None,
+            active_crafts: vec![craft],
+            contract_addresses: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_stale_state_lock_is_taken_over() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let path = dir.path().join("state.lock");
+        fs::write(&path, "1").unwrap();
+        assert!(!lock_is_stale(&path, STATE_LOCK_STALE_SECONDS));
+        
+        backdate(&path, STATE_LOCK_STALE_SECONDS + 5);
+        assert!(lock_is_stale(&path, STATE_LOCK_STALE_SECONDS));
+        let lock = StateLock::acquire_at(path.clone());
+        assert_eq!(fs::read_to_string(&path).unwrap(), std::process::id().to_string());
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_stale_worker_lock_is_taken_over() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let path = dir.path().join("worker.lock");
+        assert!(acquire_worker_lock_at(&path));
+        assert!(!acquire_worker_lock_at(&path));
+        
+        // Too old for a state lock, but a worker only refreshes its lock every
+        // WORKER_HEARTBEAT_SECONDS
+        backdate(&path, STATE_LOCK_STALE_SECONDS * 2);
+        assert!(!acquire_worker_lock_at(&path));
+        
+        backdate(&path, WORKER_STALE_SECONDS + 5);
+        assert!(acquire_worker_lock_at(&path));
+        assert!(!lock_is_stale(&path, WORKER_STALE_SECONDS));
+    }
+
+    #[test]
+    fn test_state_lock_serializes_updates() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let lock_path = dir.path().join("state.lock");
+        let counter_path = dir.path().join("counter");
+        fs::write(&counter_path, "0").unwrap();
+        
+        // Read, pause and write back: without the lock, updates would be lost
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        let _lock = StateLock::acquire_at(lock_path.clone());
+                        let count: u32 = fs::read_to_string(&counter_path).unwrap().parse().unwrap();
+                        std::thread::sleep(Duration::from_millis(1));
+                        fs::write(&counter_path, (count + 1).to_string()).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(fs::read_to_string(&counter_path).unwrap(), "80");
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn test_heartbeat_refreshes_the_lock_without_progress() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let path = dir.path().join("worker.lock");
+        fs::write(&path, "1").unwrap();
+        backdate(&path, WORKER_STALE_SECONDS + 5);
+        
+        let result = with_heartbeat(&path, Duration::from_millis(10), || {
+            std::thread::sleep(Duration::from_millis(100));
+            7
+        });
+        assert_eq!(result, 7);
+        assert!(!lock_is_stale(&path, 1));
+    }
+
+    #[test]
+    fn test_failed_craft_is_requeued_from_its_snapshot() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let snapshot_path = dir.path().join("craft_42_1.snapshot");
+        let engine = VDFEngine::new();
+        let iterations = 3 * PROGRESS_STEP;
+        
+        // Stop at the first progress report, as a worker that was killed
+        let cancel = CancellationToken::new();
+        let status = engine.compute_persistent(&craft_input(), iterations, &snapshot_path, |_| cancel.cancel(), &cancel)
+            .expect("VDF computation failed");
+        assert!(matches!(status, ComputeStatus::Cancelled(_)));
+        
+        let mut config = config_with(craft(CRAFT_FAILED, 0, None));
+        requeue_craft(&mut config, "craft_42_1", &engine, &snapshot_path).expect("Requeue failed");
+        assert_eq!(config.active_crafts[0].status, CRAFT_COMPUTING);
+        let partial = engine.load_snapshot(&snapshot_path).expect("Snapshot was discarded");
+        assert_eq!(partial.completed(), PROGRESS_STEP);
+        
+        // Only failed crafts are queued again
+        assert!(requeue_craft(&mut config, "craft_42_1", &engine, &snapshot_path).is_err());
+        assert!(requeue_craft(&mut config, "craft_42_2", &engine, &snapshot_path).is_err());
+        
+        match engine.compute_persistent(&craft_input(), iterations, &snapshot_path, |_| {}, &CancellationToken::new()) {
+            Ok(ComputeStatus::Complete(output)) => assert!(engine.verify_output(&output).expect("Verification failed")),
+            Ok(ComputeStatus::Cancelled(_)) => panic!("Resumed computation was cancelled"),
+            Err(e) => panic!("Resumed computation failed: {}", e),
+        }
+        assert!(!snapshot_path.exists());
+    }
+
+    #[test]
+    fn test_requeue_discards_an_invalid_snapshot() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let snapshot_path = dir.path().join("craft_42_1.snapshot");
+        fs::write(&snapshot_path, b"not a snapshot").unwrap();
+        
+        let mut config = config_with(craft(CRAFT_FAILED, 0, None));
+        requeue_craft(&mut config, "craft_42_1", &VDFEngine::new(), &snapshot_path).expect("Requeue failed");
+        assert_eq!(config.active_crafts[0].status, CRAFT_COMPUTING);
+        assert!(!snapshot_path.exists());
+    }
+
+    #[test]
+    fn test_load_vdf_output_reads_json_and_binary() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let output = VDFEngine::new().compute(&craft_input(), 100).expect("VDF computation failed");
+        
+        let json_path = dir.path().join("output.json");
+        fs::write(&json_path, serde_json::to_string_pretty(&output).unwrap()).unwrap();
+        let binary_path = dir.path().join("output.bin");
+        fs::write(&binary_path, output.to_bytes()).unwrap();
+        
+        for path in [&json_path, &binary_path] {
+            let loaded = load_vdf_output(path).expect("Failed to load VDF output");
+            assert_eq!(loaded.input, output.input);
+            assert_eq!(loaded.iterations, output.iterations);
+            assert_eq!(loaded.output, output.output);
+            assert_eq!(loaded.proof, output.proof);
+            assert_eq!(loaded.parameters, output.parameters);
+            assert!(VDFEngine::new().verify_output(&loaded).expect("Verification failed"));
+        }
+        
+        let garbage_path = dir.path().join("garbage");
+        fs::write(&garbage_path, b"not a VDF output").unwrap();
+        assert!(load_vdf_output(&garbage_path).is_err());
+        assert!(load_vdf_output(&dir.path().join("missing.json")).is_err());
+    }
+
+    /// Snapshot of a computation for `craft_input` stopped at its first progress report
+    fn interrupted_computation(engine: &VDFEngine, snapshot_path: &Path, iterations: u64) {
+        let cancel = CancellationToken::new();
+        let status = engine.compute_persistent(&craft_input(), iterations, snapshot_path, |_| cancel.cancel(), &cancel)
+            .expect("VDF computation failed");
+        assert!(matches!(status, ComputeStatus::Cancelled(_)));
+    }
+
+    #[test]
+    fn test_resume_continues_the_saved_computation() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let snapshot_path = dir.path().join("output.snapshot");
+        let engine = VDFEngine::new();
+        let iterations = 3 * PROGRESS_STEP;
+        interrupted_computation(&engine, &snapshot_path, iterations);
+        
+        let (input, resumed_iterations) = resumed_computation(&engine, &snapshot_path, None, None).expect("Resume failed");
+        assert_eq!(input, craft_input());
+        assert_eq!(resumed_iterations, iterations);
+        assert!(resumed_computation(&engine, &snapshot_path, Some(iterations), Some("craft")).is_ok());
+        
+        match engine.compute_persistent(&input, resumed_iterations, &snapshot_path, |_| {}, &CancellationToken::new()) {
+            Ok(ComputeStatus::Complete(output)) => {
+                assert_eq!(output.input, craft_input());
+                assert_eq!(output.iterations, iterations);
+                assert!(engine.verify_output(&output).expect("Verification failed"));
+            }
+            Ok(ComputeStatus::Cancelled(_)) => panic!("Resumed computation was cancelled"),
+            Err(e) => panic!("Resumed computation failed: {}", e),
+        }
+        assert!(!snapshot_path.exists());
+    }
+
+    #[test]
+    fn test_resume_rejects_a_different_computation() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let snapshot_path = dir.path().join("output.snapshot");
+        let engine = VDFEngine::new();
+        let iterations = 3 * PROGRESS_STEP;
+        interrupted_computation(&engine, &snapshot_path, iterations);
+        
+        let error = resumed_computation(&engine, &snapshot_path, Some(iterations + 1), None).unwrap_err();
+        assert!(error.contains("iterations"), "{}", error);
+        let error = resumed_computation(&engine, &snapshot_path, None, Some("debug")).unwrap_err();
+        assert!(error.contains("action type"), "{}", error);
+        assert!(resumed_computation(&engine, &dir.path().join("missing.snapshot"), None, None).is_err());
+        
+        // The snapshot is left for a resume with the right arguments
+        assert!(snapshot_path.exists());
+    }
+
+    #[test]
+    fn test_craft_proof_rejections() {
+        let output = VDFEngine::new().compute(&craft_input(), 100).expect("VDF computation failed");
+        assert_eq!(check_craft_proof(&craft(CRAFT_READY, 0, Some(output.clone()))), Ok(()));
+        
+        assert!(check_craft_proof(&craft(CRAFT_COMPUTING, 0, None)).is_err());
+        
+        let mut other_action = output.clone();
+        other_action.input.nonce += 1;
+        let error = check_craft_proof(&craft(CRAFT_READY, 0, Some(other_action))).unwrap_err();
+        assert!(error.contains("does not belong"), "{}", error);
+        
+        // Outputs of older releases are not accepted for new crafts
+        let mut legacy = output.clone();
+        legacy.parameters = ParameterSet::legacy();
+        let error = check_craft_proof(&craft(CRAFT_READY, 0, Some(legacy))).unwrap_err();
+        assert!(error.contains("current parameters"), "{}", error);
+        
+        // One nominal second takes more than 100 iterations
+        let error = check_craft_proof(&craft(CRAFT_READY, 1, Some(output.clone()))).unwrap_err();
+        assert!(error.contains("requires"), "{}", error);
+        
+        let mut replayed = output.clone();
+        replayed.iterations += 1;
+        let error = check_craft_proof(&craft(CRAFT_READY, 0, Some(replayed))).unwrap_err();
+        assert!(error.contains("invalid"), "{}", error);
+    }
+}
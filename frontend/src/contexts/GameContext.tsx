@@ -20,7 +20,7 @@ interface GameContextType {
   movePlayerWithProof: (areaId: number, areaType: 'street' | 'city' | 'country') => Promise<void>
   claimRewards: () => Promise<void>
   startCraft: (recipeName: string) => Promise<void>
-  completeCraft: (craftId: string) => Promise<void>
+  completeCraft: (craftId: string, vdfOutput: string) => Promise<void>
   gatherResources: (resourceType: string, quantity: number) => Promise<void>
   buyStore: (city: string, price: number) => Promise<void>
   tradeWithStore: (storeId: number, action: string, item: string, quantity: number) => Promise<void>
@@ -0,0 +1,93 @@
+interface DecodedVdfOutput {
+  readonly playerId: bigint
+  readonly actionType: string
+  readonly actionId: bigint
+  readonly iterations: bigint
+  readonly iterationsPerSecond: bigint
+  free: () => void
+}
+
+interface VdfVerifierModule {
+  default: (moduleOrPath?: string) => Promise<unknown>
+  verify: (outputBytes: Uint8Array) => boolean
+  decode: (outputBytes: Uint8Array) => DecodedVdfOutput | undefined
+}
+
+export interface VdfStatement {
+  playerId: bigint
+  actionType: string
+  actionId: bigint
+  iterations: bigint
+  iterationsPerSecond: bigint
+}
+
+class VdfService {
+  // Built by `npm run build:vdf-wasm` into frontend/public/vdf
+  private modulePath = '/vdf/zkgame_vdf_wasm.js'
+  private verifier: Promise<VdfVerifierModule> | null = null
+
+  /**
+   * Check a VDF output (hex encoding of VDFOutput::to_bytes) before submitting a craft
+   */
+  async verifyVdfOutput(vdfOutput: string): Promise<boolean> {
+    try {
+      const verifier = await this.loadVerifier()
+      return verifier.verify(this.hexToBytes(vdfOutput))
+    } catch (error) {
+      console.error('❌ [VdfService] VDF verification failed:', error)
+      this.verifier = null
+      return false
+    }
+  }
+
+  /**
+   * The action and delay a VDF output claims, without checking its proof,
+   * or null if it is not a VDF output of a published parameter set
+   */
+  async decodeVdfOutput(vdfOutput: string): Promise<VdfStatement | null> {
+    try {
+      const verifier = await this.loadVerifier()
+      const decoded = verifier.decode(this.hexToBytes(vdfOutput))
+      if (!decoded) {
+        return null
+      }
+      const statement = {
+        playerId: decoded.playerId,
+        actionType: decoded.actionType,
+        actionId: decoded.actionId,
+        iterations: decoded.iterations,
+        iterationsPerSecond: decoded.iterationsPerSecond,
+      }
+      decoded.free()
+      return statement
+    } catch (error) {
+      console.error('❌ [VdfService] VDF decoding failed:', error)
+      this.verifier = null
+      return null
+    }
+  }
+
+  private loadVerifier(): Promise<VdfVerifierModule> {
+    if (!this.verifier) {
+      this.verifier = import(/* @vite-ignore */ this.modulePath).then(async (module: VdfVerifierModule) => {
+        await module.default()
+        return module
+      })
+    }
+    return this.verifier
+  }
+
+  private hexToBytes(hex: string): Uint8Array {
+    const digits = hex.startsWith('0x') ? hex.slice(2) : hex
+    if (digits.length % 2 !== 0 || /[^0-9a-fA-F]/.test(digits)) {
+      throw new Error('VDF output is not valid hex')
+    }
+    const bytes = new Uint8Array(digits.length / 2)
+    for (let i = 0; i < bytes.length; i++) {
+      bytes[i] = parseInt(digits.slice(2 * i, 2 * i + 2), 16)
+    }
+    return bytes
+  }
+}
+
+export const vdfService = new VdfService()
@@ -2,12 +2,16 @@ import { create } from 'zustand'
 import { devtools } from 'zustand/middleware'
 import { proofService, MovementProofInputs } from '../services/proofService'
 import { contractService, ContractConfig } from '../services/contractService'
+import { vdfService } from '../services/vdfService'
 
 // localStorage helper functions
 const STORAGE_KEY = 'zkgame_player_data'
 const BACKUP_KEY = 'zkgame_backups'
 const SETTINGS_KEY = 'zkgame_settings'
 
+// Game server that checks craft proofs, see backend/server.js
+const BACKEND_URL = 'http://localhost:3001'
+
 // Enhanced storage functions with better error handling and validation
 const savePlayerToStorage = (player: PlayerState) => {
   if (typeof window !== 'undefined') {
@@ -286,7 +290,7 @@ export interface GameActions {
   movePlayerWithProof: (areaId: number, areaType: 'street' | 'city' | 'country') => Promise<void>
   claimRewards: () => Promise<void>
   startCraft: (recipeName: string) => Promise<void>
-  completeCraft: (craftId: string) => Promise<void>
+  completeCraft: (craftId: string, vdfOutput: string) => Promise<void>
   gatherResources: (resourceType: string, quantity: number) => Promise<void>
   buyStore: (city: string, price: number) => Promise<void>
   tradeWithStore: (storeId: number, action: string, item: string, quantity: number) => Promise<void>
@@ -691,7 +695,7 @@ export const useGameStore = create<GameState & GameActions>()(
         }
       },
 
-      completeCraft: async (craftId: string) => {
+      completeCraft: async (craftId: string, vdfOutput: string) => {
         const { player, activeCrafts } = get()
         if (!player) return
 
@@ -713,25 +717,56 @@ export const useGameStore = create<GameState & GameActions>()(
           // Complete the craft
           const recipes: Record<string, any> = {
             iron_sword: {
+              id: 1,
               required_materials: [
                 { item_type: 'iron_ore', quantity: 3 },
                 { item_type: 'wood', quantity: 1 }
               ],
+              required_time_seconds: 3600,
               output_item: { type: 'iron_sword', quantity: 1 },
               experience_reward: 100,
             },
             basic_tool: {
+              id: 5,
               required_materials: [
                 { item_type: 'iron_ore', quantity: 1 },
                 { item_type: 'wood', quantity: 1 }
               ],
+              required_time_seconds: 900,
               output_item: { type: 'basic_tool', quantity: 1 },
               experience_reward: 25,
             }
           }
 
           const recipe = recipes[craft.recipeName]
-          
+
+          // vdfOutput is the hex encoding of VDFOutput::to_bytes; check it
+          // here so a bad proof is caught before it is submitted
+          const statement = await vdfService.decodeVdfOutput(vdfOutput)
+          if (!statement) {
+            throw new Error('Malformed VDF proof')
+          }
+          if (statement.playerId !== BigInt(player.playerId) || statement.actionType !== 'craft' || statement.actionId !== BigInt(recipe.id)) {
+            throw new Error('VDF proof belongs to another action')
+          }
+          const requiredIterations = BigInt(recipe.required_time_seconds) * statement.iterationsPerSecond
+          if (statement.iterations < requiredIterations) {
+            throw new Error(`VDF proof covers ${statement.iterations} iterations, recipe requires ${requiredIterations}`)
+          }
+          if (!(await vdfService.verifyVdfOutput(vdfOutput))) {
+            throw new Error('Invalid VDF proof')
+          }
+
+          const response = await fetch(`${BACKEND_URL}/api/game/craft`, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ playerId: player.playerId, recipeName: craft.recipeName, vdfOutput }),
+          })
+          if (!response.ok) {
+            const body = await response.json().catch(() => null)
+            throw new Error(body?.error || `Craft was rejected with status ${response.status}`)
+          }
+
           // Consume materials
           const newInventory = { ...player.inventory }
           recipe.required_materials.forEach((req: any) => {
@@ -31,15 +31,37 @@ fn test_vdf_verification() {
     };
     
     let result = engine.compute(&input, 1000).expect("VDF computation failed");
-    let input_seed = engine.generate_input_seed(&input);
-    let output = rug::Integer::from_str_radix(&result.output, 10).expect("Failed to parse output");
-    let proof = rug::Integer::from_str_radix(&result.proof, 10).expect("Failed to parse proof");
+    let output = result.output_element().expect("Failed to parse output");
+    let proof = zkgame_vdf::element_from_bytes(&result.proof).expect("Failed to parse proof");
     
-    let is_valid = engine.verify(&input_seed, &output, 1000, &proof)
+    let is_valid = engine.verify(&input, &output, 1000, &proof)
         .expect("Verification failed");
     assert!(is_valid);
 }
 
+#[test]
+fn test_vdf_verify_output() {
+    let engine = VDFEngine::new();
+    let input = VDFInput {
+        player_id: 1,
+        action_type: "test".to_string(),
+        action_id: 1,
+        timestamp: 1234567890,
+        nonce: 1,
+        random_salt: 12345,
+    };
+    
+    // A stored output verifies in one call, no seed or value parsing needed
+    let result = engine.compute(&input, 1000).expect("VDF computation failed");
+    let json = serde_json::to_string(&result).expect("Serialization failed");
+    let stored: zkgame_vdf::VDFOutput = serde_json::from_str(&json).expect("Deserialization failed");
+    assert!(engine.verify_output(&stored).expect("Verification failed"));
+    
+    let mut replayed = stored.clone();
+    replayed.input.action_id = 2;
+    assert!(!engine.verify_output(&replayed).expect("Verification failed"));
+}
+
 #[test]
 fn test_vdf_time_conversion() {
     let engine = VDFEngine::new();
@@ -125,13 +147,12 @@ fn test_vdf_invalid_proof() {
     };
     
     let result = engine.compute(&input, 1000).expect("VDF computation failed");
-    let input_seed = engine.generate_input_seed(&input);
-    let output = rug::Integer::from_str_radix(&result.output, 10).expect("Failed to parse output");
+    let output = result.output_element().expect("Failed to parse output");
     
     // Create invalid proof
     let invalid_proof = rug::Integer::from(12345);
     
-    let is_valid = engine.verify(&input_seed, &output, 1000, &invalid_proof)
+    let is_valid = engine.verify(&input, &output, 1000, &invalid_proof)
         .expect("Verification failed");
     assert!(!is_valid, "Invalid proof should be rejected");
 }
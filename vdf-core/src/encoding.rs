@@ -0,0 +1,92 @@
+//! Fixed-width elements and the length-prefixed fields of binary encodings
+//!
+//! RSA group elements are written as 256-byte big-endian integers, the
+//! operand layout of the EVM modexp precompile (EIP-198). All integers in
+//! the binary encodings are big-endian and variable-length fields carry a
+//! `u32` length prefix.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Size of one serialized RSA-2048 group element
+pub const ELEMENT_BYTES: usize = 256;
+
+/// Why bytes are not a valid encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The leading version byte is not one this crate reads
+    UnsupportedVersion(u8),
+    /// The scheme byte names no known scheme
+    UnknownScheme(u8),
+    /// Any other layout error
+    Malformed(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(version) => write!(f, "Unsupported output format version {}", version),
+            DecodeError::UnknownScheme(tag) => write!(f, "Unknown scheme tag {}", tag),
+            DecodeError::Malformed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Left-pad big-endian `digits` to the fixed element width
+pub fn pad_element(digits: &[u8]) -> Result<[u8; ELEMENT_BYTES], DecodeError> {
+    if digits.len() > ELEMENT_BYTES {
+        return Err(DecodeError::Malformed("Group element is wider than 2048 bits"));
+    }
+
+    let mut bytes = [0u8; ELEMENT_BYTES];
+    bytes[ELEMENT_BYTES - digits.len()..].copy_from_slice(digits);
+    Ok(bytes)
+}
+
+/// Append `field` with a `u32` length prefix
+pub fn write_field(bytes: &mut Vec<u8>, field: &[u8]) {
+    bytes.extend_from_slice(&(field.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(field);
+}
+
+/// Cursor over a binary encoding
+pub struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::Malformed("Unexpected end of encoded output"));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub fn u64(&mut self) -> Result<u64, DecodeError> {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(word))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, DecodeError> {
+        let mut word = [0u8; 4];
+        word.copy_from_slice(self.take(4)?);
+        Ok(u32::from_be_bytes(word))
+    }
+
+    /// Field written by `write_field`
+    pub fn field(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+}
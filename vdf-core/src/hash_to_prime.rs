@@ -0,0 +1,130 @@
+//! Versioned hash-to-prime for Wesolowski challenges
+//!
+//! Version 2 maps a statement to a 256-bit prime `l` as follows. All
+//! integers are big-endian, `group`, `input` and `output` are the
+//! fixed-width encodings of the group description and the two elements, and
+//! `context` is the game context from `statement::challenge_context`.
+//!
+//! ```text
+//! h_i = SHA-256(TAG || u32(len(group)) || group || u64(iterations)
+//!               || u32(len(context)) || context
+//!               || u32(len(input)) || input || u32(len(output)) || output
+//!               || u32(i))
+//! c_i = h_i | 2^255 | 1
+//! l   = c_i for the smallest i such that c_i passes Miller-Rabin
+//!       with every base in MILLER_RABIN_BASES
+//! ```
+//!
+//! with `TAG = "zkgame-vdf-hash-to-prime-v2"`. The bases are fixed rather than
+//! random so that the Solidity and JS verifiers pick the same prime;
+//! `vdf/test_vectors/hash_to_prime_v2.json` lists the expected counter and
+//! prime for a few statements. Version 1 had no context field.
+
+use num_bigint::BigUint;
+use num_traits::One;
+use sha2::{Sha256, Digest};
+
+/// Version of the hash-to-prime described above
+pub const HASH_TO_PRIME_VERSION: u8 = 2;
+
+/// Domain separation tag prefixed to every hash
+pub const HASH_TO_PRIME_TAG: &[u8] = b"zkgame-vdf-hash-to-prime-v2";
+
+/// Size of the challenge prime
+pub const CHALLENGE_BITS: u32 = 256;
+
+/// Miller-Rabin bases, the first 20 primes
+pub const MILLER_RABIN_BASES: [u32; 20] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71];
+
+/// Candidates tried before giving up; about 89 are needed on average
+pub const MAX_ATTEMPTS: u32 = 1 << 16;
+
+/// Challenge prime together with the counter of the candidate that was
+/// accepted, `None` if no candidate within `MAX_ATTEMPTS` is prime
+pub fn hash_to_prime_with_counter(group: &[u8], iterations: u64, context: &[u8], input: &[u8], output: &[u8]) -> Option<(u32, BigUint)> {
+    let mut prefix = Sha256::new();
+    prefix.update(HASH_TO_PRIME_TAG);
+    for (index, part) in [group, context, input, output].into_iter().enumerate() {
+        prefix.update((part.len() as u32).to_be_bytes());
+        prefix.update(part);
+        if index == 0 {
+            prefix.update(iterations.to_be_bytes());
+        }
+    }
+
+    for counter in 0..MAX_ATTEMPTS {
+        let mut hasher = prefix.clone();
+        hasher.update(counter.to_be_bytes());
+        let mut candidate = BigUint::from_bytes_be(&hasher.finalize());
+        candidate.set_bit(u64::from(CHALLENGE_BITS - 1), true);
+        candidate.set_bit(0, true);
+        if is_prime_fixed_bases(&candidate) {
+            return Some((counter, candidate));
+        }
+    }
+    None
+}
+
+/// Miller-Rabin with the fixed `MILLER_RABIN_BASES`, for odd `n` above the largest base
+pub fn is_prime_fixed_bases(n: &BigUint) -> bool {
+    if *n <= BigUint::from(MILLER_RABIN_BASES[MILLER_RABIN_BASES.len() - 1]) || !n.bit(0) {
+        return false;
+    }
+    let n_minus_one = n - 1u32;
+    let shift = n_minus_one.trailing_zeros().unwrap_or(0);
+    let odd = &n_minus_one >> shift;
+
+    'bases: for base in MILLER_RABIN_BASES {
+        let mut x = BigUint::from(base).modpow(&odd, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..shift {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_published_vectors() {
+        let vectors: Value = serde_json::from_str(include_str!("../../vdf/test_vectors/hash_to_prime_v2.json"))
+            .expect("Invalid test vector file");
+        assert_eq!(vectors["version"].as_u64(), Some(HASH_TO_PRIME_VERSION as u64));
+        assert_eq!(vectors["tag"].as_str().map(str::as_bytes), Some(HASH_TO_PRIME_TAG));
+
+        let vectors = vectors["vectors"].as_array().expect("Missing vectors");
+        assert!(!vectors.is_empty());
+        for vector in vectors {
+            let field = |name: &str| hex::decode(vector[name].as_str().expect("Missing field")).expect("Invalid hex in test vector");
+            let (counter, prime) = hash_to_prime_with_counter(
+                &field("group"),
+                vector["iterations"].as_u64().expect("Missing iterations"),
+                &field("context"),
+                &field("input"),
+                &field("output"),
+            ).expect("Hash to prime failed");
+            assert_eq!(Some(counter as u64), vector["counter"].as_u64());
+            assert_eq!(prime, BigUint::from_bytes_be(&field("prime")));
+        }
+    }
+
+    #[test]
+    fn test_fixed_base_primality() {
+        // 2^255 - 19 is prime, 2^256 - 1 and the Carmichael number 561 are not
+        let p = (BigUint::one() << 255u32) - 19u32;
+        assert!(is_prime_fixed_bases(&p));
+        assert!(!is_prime_fixed_bases(&((BigUint::one() << 256u32) - 1u32)));
+        assert!(!is_prime_fixed_bases(&BigUint::from(561u32)));
+        assert!(!is_prime_fixed_bases(&BigUint::from(2u32)));
+    }
+}
@@ -0,0 +1,35 @@
+//! Definitions every zkgame VDF verifier has to agree on
+//!
+//! `zkgame-vdf` computes and verifies proofs, `zkgame-vdf-wasm` verifies
+//! them in the browser and in Node. Both take the pieces that decide whether
+//! a proof is valid from this crate instead of keeping their own copies:
+//!
+//! - the RSA-2048 modulus and the element width (`RSA_2048_MODULUS`, `ELEMENT_BYTES`)
+//! - the input seed and the challenge context (`statement`)
+//! - hash-to-prime version 2 (`hash_to_prime`)
+//! - the Pietrzak folding challenge (`pietrzak`)
+//! - the binary `VDFOutput` encoding (`statement`, `encoding`)
+//! - the registry of published parameter sets (`params`)
+//!
+//! The crate is `no_std` with `alloc` and builds for `wasm32-unknown-unknown`.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod encoding;
+pub mod hash_to_prime;
+pub mod params;
+pub mod pietrzak;
+pub mod statement;
+
+pub use encoding::{DecodeError, ELEMENT_BYTES};
+pub use params::PublishedSet;
+pub use statement::{challenge_context, Scheme, Statement, VDFInput, PROOF_VERSION};
+
+/// RSA-2048 modulus (publicly known, no factorization exists)
+/// This is the standard RSA-2048 modulus used for VDF computations
+pub const RSA_2048_MODULUS: &str = "25195908475657893494027183240048398571429282126204032027777137836043662020707595556264018525880784406918290641249515082189298559149176184502808489120072844992687392807287776735971418347270261896375014971824691165077613379859095700097330459748808428401797429100642458691817195118746121515172654632282216869987549182422433637259085141865462043576798423387184774447920739934236584823824281198163815010674810451660377306056201619676256133844143603833904414952634432190114657544454178424020924616515723350778707749817125772467962926386356373289912154831438167899885040445364023527381951378636564391212010397122822120720357";
+
+/// Default upper bound on iterations, matches `vdf.max_iterations` in config/circuits_config.json
+pub const DEFAULT_MAX_ITERATIONS: u64 = 100_000_000;
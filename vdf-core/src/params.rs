@@ -0,0 +1,73 @@
+//! Registry of published parameter sets
+//!
+//! A parameter set fixes everything a proof depends on besides the action
+//! itself; see `zkgame_vdf::params` for the history. Every verifier looks
+//! sets up here by the name recorded in the output:
+//!
+//! | Set          | Group                        | Hash | Statement | Status      |
+//! |--------------|------------------------------|------|-----------|-------------|
+//! | `rsa2048-v1` | RSA-2048, `(Z/NZ)*`          | 2    | 1         | verify only |
+//! | `rsa2048-v2` | RSA-2048, `(Z/NZ)* / {±1}`   | 2    | 2         | current     |
+
+use crate::hash_to_prime::HASH_TO_PRIME_VERSION;
+use crate::statement::PROOF_VERSION;
+
+/// Modulus id of the RSA-2048 challenge number
+pub const RSA_2048_MODULUS_ID: &str = "rsa-2048";
+
+/// Name of the set outputs without a recorded set belong to
+pub const LEGACY_PARAMETER_SET: &str = "rsa2048-v1";
+
+/// Name of the set new outputs are computed with
+pub const CURRENT_PARAMETER_SET: &str = "rsa2048-v2";
+
+/// Protocol rate mapping an action's nominal duration to its required iterations.
+/// Must match `vdf.iterations_per_second` in config/circuits_config.json and
+/// `ITERATIONS_PER_SECOND` in circuits/timeCraft.circom.
+pub const PROTOCOL_ITERATIONS_PER_SECOND: u64 = 278;
+
+/// Values of a published parameter set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublishedSet {
+    pub name: &'static str,
+    pub modulus_id: &'static str,
+    pub hash_to_prime_version: u8,
+    pub proof_version: u8,
+    pub security_bits: u32,
+    pub iterations_per_second: u64,
+}
+
+impl PublishedSet {
+    /// Whether RSA elements are taken up to sign
+    pub fn quotient_group(&self) -> bool {
+        self.proof_version >= 2
+    }
+}
+
+/// RSA-2048 in `(Z/NZ)*` with statement version 1
+pub const RSA2048_V1: PublishedSet = PublishedSet {
+    name: LEGACY_PARAMETER_SET,
+    modulus_id: RSA_2048_MODULUS_ID,
+    hash_to_prime_version: 2,
+    proof_version: 1,
+    security_bits: 112,
+    iterations_per_second: 278,
+};
+
+/// RSA-2048 in the quotient group `{±1}` with statement version 2
+pub const RSA2048_V2: PublishedSet = PublishedSet {
+    name: CURRENT_PARAMETER_SET,
+    modulus_id: RSA_2048_MODULUS_ID,
+    hash_to_prime_version: HASH_TO_PRIME_VERSION,
+    proof_version: PROOF_VERSION,
+    security_bits: 112,
+    iterations_per_second: PROTOCOL_ITERATIONS_PER_SECOND,
+};
+
+/// Every published set, oldest first
+pub static PUBLISHED_SETS: [PublishedSet; 2] = [RSA2048_V1, RSA2048_V2];
+
+/// Published set called `name`
+pub fn published_set(name: &str) -> Option<&'static PublishedSet> {
+    PUBLISHED_SETS.iter().find(|set| set.name == name)
+}
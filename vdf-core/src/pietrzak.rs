@@ -0,0 +1,38 @@
+//! Folding challenges of Pietrzak halving proofs
+//!
+//! Each round folds `x^(2^T) = y` with the midpoint `mu` into a statement of
+//! half the length, using the challenge
+//!
+//! ```text
+//! r = SHA-256(TAG || u32(len(context)) || context || x || y || mu || u64(T))[..16]
+//! ```
+//!
+//! read as a big-endian integer, where `x`, `y` and `mu` are fixed-width
+//! element encodings and `TAG = "zkgame-vdf-pietrzak-challenge-v1"`.
+
+use num_bigint::BigUint;
+use sha2::{Sha256, Digest};
+
+/// Statements at or below this many squarings are checked directly
+pub const TAIL_ITERATIONS: u64 = 64;
+
+/// Size of the folding challenge `r` in bits
+pub const CHALLENGE_BITS: u64 = 128;
+
+/// Domain separation tag for the folding challenges
+pub const CHALLENGE_TAG: &[u8] = b"zkgame-vdf-pietrzak-challenge-v1";
+
+/// Folding challenge for the round halving `x^(2^t) = y` at `mu`
+pub fn folding_challenge(context: &[u8], x: &[u8], y: &[u8], mu: &[u8], t: u64) -> BigUint {
+    let mut hasher = Sha256::new();
+    hasher.update(CHALLENGE_TAG);
+    hasher.update((context.len() as u32).to_be_bytes());
+    hasher.update(context);
+    hasher.update(x);
+    hasher.update(y);
+    hasher.update(mu);
+    hasher.update(t.to_be_bytes());
+    let hash = hasher.finalize();
+
+    BigUint::from_bytes_be(&hash[..(CHALLENGE_BITS / 8) as usize])
+}
@@ -0,0 +1,250 @@
+//! The statement a proof is made for and the binary `VDFOutput` encoding
+//!
+//! A proof shows `output = x^(2^iterations)` where `x` is derived from the
+//! action in `VDFInput` (`VDFInput::seed_digest`). Its challenges also bind
+//! the statement version, the scheme and the action type through
+//! `challenge_context`, so a proof only verifies for the action it was made
+//! for.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use sha2::{Sha256, Digest};
+
+use crate::encoding::{write_field, DecodeError, Reader};
+use crate::params::LEGACY_PARAMETER_SET;
+
+/// Version of the statement bound into proof challenges
+///
+/// Version 2 takes RSA elements in the quotient group `{±1}`; version 1
+/// proofs used plain residues and stay verifiable under parameter set
+/// `rsa2048-v1`, see `params`.
+pub const PROOF_VERSION: u8 = 2;
+
+/// Version byte leading the binary `VDFOutput` encoding
+///
+/// Version 2 added the parameter set name; version 1 encodings belong to
+/// `LEGACY_PARAMETER_SET`.
+pub const OUTPUT_FORMAT_VERSION: u8 = 2;
+
+/// VDF input seed for binding to specific actions
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VDFInput {
+    pub player_id: u64,
+    pub action_type: String,
+    pub action_id: u64,
+    pub timestamp: u64,
+    pub nonce: u64,
+    pub random_salt: u64,
+}
+
+impl VDFInput {
+    /// Hash the action details into the 256-bit seed shared by all backends,
+    /// read as a little-endian integer when mapped to a group element
+    pub fn seed_digest(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.player_id.to_le_bytes());
+        hasher.update(self.action_type.as_bytes());
+        hasher.update(self.action_id.to_le_bytes());
+        hasher.update(self.timestamp.to_le_bytes());
+        hasher.update(self.nonce.to_le_bytes());
+        hasher.update(self.random_salt.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Append the fixed-order encoding
+    /// `player_id u64 | action_id u64 | timestamp u64 | nonce u64 | random_salt u64 | action_type`
+    pub fn write_to(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.player_id.to_be_bytes());
+        bytes.extend_from_slice(&self.action_id.to_be_bytes());
+        bytes.extend_from_slice(&self.timestamp.to_be_bytes());
+        bytes.extend_from_slice(&self.nonce.to_be_bytes());
+        bytes.extend_from_slice(&self.random_salt.to_be_bytes());
+        write_field(bytes, self.action_type.as_bytes());
+    }
+
+    /// Read the encoding written by `write_to`
+    pub fn read_from(reader: &mut Reader<'_>) -> Result<Self, DecodeError> {
+        let player_id = reader.u64()?;
+        let action_id = reader.u64()?;
+        let timestamp = reader.u64()?;
+        let nonce = reader.u64()?;
+        let random_salt = reader.u64()?;
+        let action_type = String::from_utf8(reader.field()?.to_vec())
+            .map_err(|_| DecodeError::Malformed("Action type is not UTF-8"))?;
+        Ok(Self {
+            player_id,
+            action_type,
+            action_id,
+            timestamp,
+            nonce,
+            random_salt,
+        })
+    }
+}
+
+/// Proof scheme of a statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Wesolowski,
+    Pietrzak,
+}
+
+impl Scheme {
+    /// Byte identifying the scheme in binary encodings and challenges
+    pub fn tag(&self) -> u8 {
+        match self {
+            Scheme::Wesolowski => 0,
+            Scheme::Pietrzak => 1,
+        }
+    }
+
+    /// Inverse of `tag`
+    pub fn from_tag(tag: u8) -> Result<Self, DecodeError> {
+        match tag {
+            0 => Ok(Scheme::Wesolowski),
+            1 => Ok(Scheme::Pietrzak),
+            _ => Err(DecodeError::UnknownScheme(tag)),
+        }
+    }
+}
+
+/// Game context bound into the challenge of a proof:
+/// `version u8 | scheme tag u8 | u32 length | action_type`, where `version`
+/// is the statement version of the output's parameter set.
+/// The remaining `VDFInput` fields enter through the input seed.
+pub fn challenge_context(version: u8, scheme: Scheme, action_type: &str) -> Vec<u8> {
+    let mut context = Vec::with_capacity(6 + action_type.len());
+    context.push(version);
+    context.push(scheme.tag());
+    context.extend_from_slice(&(action_type.len() as u32).to_be_bytes());
+    context.extend_from_slice(action_type.as_bytes());
+    context
+}
+
+/// Fields of an encoded `VDFOutput`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statement {
+    pub scheme: Scheme,
+    /// Name of the parameter set, see `params`
+    pub parameters: String,
+    pub input: VDFInput,
+    pub iterations: u64,
+    /// Informational, not part of what the proof shows
+    pub computation_time: f64,
+    /// Fixed-width output element
+    pub output: Vec<u8>,
+    /// Concatenated fixed-width proof elements
+    pub proof: Vec<u8>,
+}
+
+impl Statement {
+    /// Canonical binary encoding
+    ///
+    /// All integers are big-endian; variable-length fields carry a `u32` length prefix:
+    /// `version u8 | scheme u8 | parameter set name | player_id u64 |
+    /// action_id u64 | timestamp u64 | nonce u64 | random_salt u64 |
+    /// action_type | iterations u64 | computation_time f64 | output | proof`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(80 + self.input.action_type.len() + self.output.len() + self.proof.len());
+        bytes.push(OUTPUT_FORMAT_VERSION);
+        bytes.push(self.scheme.tag());
+        write_field(&mut bytes, self.parameters.as_bytes());
+        self.input.write_to(&mut bytes);
+        bytes.extend_from_slice(&self.iterations.to_be_bytes());
+        bytes.extend_from_slice(&self.computation_time.to_bits().to_be_bytes());
+        write_field(&mut bytes, &self.output);
+        write_field(&mut bytes, &self.proof);
+        bytes
+    }
+
+    /// Decode the encoding written by `to_bytes`, or format version 1
+    /// without the parameter set name
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader::new(bytes);
+        let version = reader.take(1)?[0];
+        if version != 1 && version != OUTPUT_FORMAT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let scheme = Scheme::from_tag(reader.take(1)?[0])?;
+        let parameters = if version == 1 {
+            String::from(LEGACY_PARAMETER_SET)
+        } else {
+            String::from_utf8(reader.field()?.to_vec())
+                .map_err(|_| DecodeError::Malformed("Parameter set name is not UTF-8"))?
+        };
+        let input = VDFInput::read_from(&mut reader)?;
+        let iterations = reader.u64()?;
+        let computation_time = f64::from_bits(reader.u64()?);
+        let output = reader.field()?.to_vec();
+        let proof = reader.field()?.to_vec();
+        if !reader.is_empty() {
+            return Err(DecodeError::Malformed("Trailing bytes after proof"));
+        }
+
+        Ok(Self {
+            scheme,
+            parameters,
+            input,
+            iterations,
+            computation_time,
+            output,
+            proof,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn statement() -> Statement {
+        Statement {
+            scheme: Scheme::Pietrzak,
+            parameters: String::from("rsa2048-v2"),
+            input: VDFInput {
+                player_id: 42,
+                action_type: String::from("craft"),
+                action_id: 7,
+                timestamp: 1234567890,
+                nonce: 3,
+                random_salt: 99,
+            },
+            iterations: 1000,
+            computation_time: 0.5,
+            output: vec![1; 256],
+            proof: vec![2; 512],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let statement = statement();
+        assert_eq!(Statement::from_bytes(&statement.to_bytes()), Ok(statement.clone()));
+
+        let mut bytes = statement.to_bytes();
+        bytes.push(0);
+        assert_eq!(Statement::from_bytes(&bytes), Err(DecodeError::Malformed("Trailing bytes after proof")));
+        bytes[0] = 3;
+        assert_eq!(Statement::from_bytes(&bytes), Err(DecodeError::UnsupportedVersion(3)));
+    }
+
+    #[test]
+    fn test_format_version_1_is_legacy() {
+        let statement = statement();
+        let mut version_1 = statement.to_bytes();
+        version_1.drain(2..2 + 4 + statement.parameters.len());
+        version_1[0] = 1;
+        let decoded = Statement::from_bytes(&version_1).expect("Decoding failed");
+        assert_eq!(decoded.parameters, LEGACY_PARAMETER_SET);
+        assert_eq!(decoded.input, statement.input);
+    }
+
+    #[test]
+    fn test_challenge_context_layout() {
+        assert_eq!(challenge_context(2, Scheme::Pietrzak, "craft"), [&[2, 1, 0, 0, 0, 5][..], b"craft"].concat());
+        assert_ne!(challenge_context(1, Scheme::Pietrzak, "craft"), challenge_context(2, Scheme::Pietrzak, "craft"));
+    }
+}
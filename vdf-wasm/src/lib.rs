@@ -0,0 +1,227 @@
+//! Verify-only VDF proof checker for WebAssembly
+//!
+//! Checks the binary `VDFOutput` encoding written by `zkgame-vdf`
+//! (`VDFOutput::to_bytes`) without std or GMP, so the browser client and
+//! `backend/server.js` can reject a bad craft proof before it is submitted.
+//! The statement, encodings, challenges and parameter sets come from
+//! `zkgame-vdf-core`, which `zkgame-vdf` uses as well; the tests cross-check
+//! both crates.
+//!
+//! Build with `wasm-pack build --target web` for the frontend or
+//! `--target nodejs` for the backend (`npm run build:vdf-wasm`).
+
+#![cfg_attr(target_arch = "wasm32", no_std)]
+
+extern crate alloc;
+
+pub mod verify;
+
+use alloc::string::String;
+use core::fmt;
+
+use wasm_bindgen::prelude::wasm_bindgen;
+use zkgame_vdf_core::encoding::DecodeError;
+use zkgame_vdf_core::params::{published_set, PublishedSet};
+
+pub use verify::verify_statement;
+pub use zkgame_vdf_core::Statement;
+
+#[cfg(target_arch = "wasm32")]
+#[global_allocator]
+static ALLOCATOR: dlmalloc::GlobalDlmalloc = dlmalloc::GlobalDlmalloc;
+
+#[cfg(target_arch = "wasm32")]
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    core::arch::wasm32::unreachable()
+}
+
+/// Errors for outputs that cannot be checked at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The bytes are not a `VDFOutput` encoding this crate understands
+    Malformed(DecodeError),
+    /// The output names a parameter set that is not published
+    UnknownParameters,
+    /// More iterations than the protocol allows
+    TooManyIterations,
+    /// Hash-to-prime ran out of candidates
+    NoChallengePrime,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Malformed(reason) => write!(f, "Malformed output: {}", reason),
+            VerifyError::UnknownParameters => write!(f, "Unknown parameter set"),
+            VerifyError::TooManyIterations => write!(f, "Too many iterations"),
+            VerifyError::NoChallengePrime => write!(f, "No challenge prime found"),
+        }
+    }
+}
+
+impl From<DecodeError> for VerifyError {
+    fn from(error: DecodeError) -> Self {
+        VerifyError::Malformed(error)
+    }
+}
+
+/// Check an encoded `VDFOutput`, reporting why undecodable outputs fail
+pub fn verify_output_bytes(output_bytes: &[u8]) -> Result<bool, VerifyError> {
+    verify_statement(&Statement::from_bytes(output_bytes)?)
+}
+
+/// Whether `output_bytes` is a `VDFOutput` with a valid proof
+#[wasm_bindgen]
+pub fn verify(output_bytes: &[u8]) -> bool {
+    verify_output_bytes(output_bytes).unwrap_or(false)
+}
+
+/// The action and delay an encoded `VDFOutput` claims, for checking that a
+/// proof was made for the request it comes with
+#[wasm_bindgen]
+pub struct DecodedOutput {
+    statement: Statement,
+    parameters: &'static PublishedSet,
+}
+
+#[wasm_bindgen]
+impl DecodedOutput {
+    #[wasm_bindgen(getter, js_name = playerId)]
+    pub fn player_id(&self) -> u64 {
+        self.statement.input.player_id
+    }
+
+    #[wasm_bindgen(getter, js_name = actionType)]
+    pub fn action_type(&self) -> String {
+        self.statement.input.action_type.clone()
+    }
+
+    #[wasm_bindgen(getter, js_name = actionId)]
+    pub fn action_id(&self) -> u64 {
+        self.statement.input.action_id
+    }
+
+    /// Unix time in seconds the action was started at
+    #[wasm_bindgen(getter)]
+    pub fn timestamp(&self) -> u64 {
+        self.statement.input.timestamp
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> u64 {
+        self.statement.input.nonce
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> u64 {
+        self.statement.iterations
+    }
+
+    /// Rate of the output's parameter set, to turn a nominal duration into iterations
+    #[wasm_bindgen(getter, js_name = iterationsPerSecond)]
+    pub fn iterations_per_second(&self) -> u64 {
+        self.parameters.iterations_per_second
+    }
+}
+
+/// Decode `output_bytes` without checking the proof, `None` if it is not a
+/// `VDFOutput` encoding of a published parameter set
+#[wasm_bindgen]
+pub fn decode(output_bytes: &[u8]) -> Option<DecodedOutput> {
+    let statement = Statement::from_bytes(output_bytes).ok()?;
+    let parameters = published_set(&statement.parameters)?;
+    Some(DecodedOutput { statement, parameters })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zkgame_vdf::{SchemeKind, VDFEngine, VDFInput};
+
+    fn input() -> VDFInput {
+        VDFInput {
+            player_id: 4,
+            action_type: "craft".to_string(),
+            action_id: 17,
+            timestamp: 1234567890,
+            nonce: 3,
+            random_salt: 4096,
+        }
+    }
+
+    #[test]
+    fn test_accepts_outputs_from_the_engine() {
+        for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+            let output = VDFEngine::new().with_scheme(scheme).compute(&input(), 600).expect("VDF computation failed");
+            assert!(verify(&output.to_bytes()), "{:?} output rejected", scheme);
+        }
+    }
+
+    #[test]
+    fn test_accepts_short_delays() {
+        // Below 256 iterations the honest Wesolowski proof is 1
+        for iterations in [0, 1, 2, 10, 100, 200, 255] {
+            let output = VDFEngine::new().compute(&input(), iterations).expect("VDF computation failed");
+            assert!(verify(&output.to_bytes()), "T = {} rejected", iterations);
+        }
+    }
+
+    #[test]
+    fn test_decodes_the_claimed_action() {
+        let output = VDFEngine::new().compute(&input(), 600).expect("VDF computation failed");
+        let decoded = decode(&output.to_bytes()).expect("Decoding failed");
+        assert_eq!(decoded.player_id(), 4);
+        assert_eq!(decoded.action_type(), "craft");
+        assert_eq!(decoded.action_id(), 17);
+        assert_eq!(decoded.timestamp(), 1234567890);
+        assert_eq!(decoded.nonce(), 3);
+        assert_eq!(decoded.iterations(), 600);
+        assert_eq!(decoded.iterations_per_second(), VDFEngine::new().time_to_iterations(1));
+        assert!(decode(&[]).is_none());
+    }
+
+    #[test]
+    fn test_rejects_altered_outputs() {
+        let output = VDFEngine::new().compute(&input(), 600).expect("VDF computation failed");
+
+        let mut replayed = output.clone();
+        replayed.iterations = 601;
+        assert!(!verify(&replayed.to_bytes()));
+
+        let mut other_action = output.clone();
+        other_action.input.action_type = "gather".to_string();
+        assert!(!verify(&other_action.to_bytes()));
+
+        let mut relabelled = output.clone();
+        relabelled.scheme = SchemeKind::Pietrzak;
+        assert!(!verify(&relabelled.to_bytes()));
+
+        let mut other_set = output.clone();
+        other_set.parameters = zkgame_vdf::ParameterSet::legacy();
+        assert!(!verify(&other_set.to_bytes()));
+
+        let mut zero = output.clone();
+        zero.output = vec![0; zkgame_vdf::ELEMENT_BYTES];
+        zero.proof = vec![0; zkgame_vdf::ELEMENT_BYTES];
+        assert!(!verify(&zero.to_bytes()));
+
+        // N - y and N - pi satisfy the equation in (Z/NZ)* but are not canonical
+        let modulus = zkgame_vdf::BigUintRsaGroup::rsa_2048().modulus().clone();
+        let negate = |bytes: &[u8]| {
+            let digits = (&modulus - num_bigint::BigUint::from_bytes_be(bytes)).to_bytes_be();
+            let mut negated = vec![0u8; zkgame_vdf::ELEMENT_BYTES - digits.len()];
+            negated.extend_from_slice(&digits);
+            negated
+        };
+        let mut negated = output.clone();
+        negated.output = negate(&output.output);
+        negated.proof = negate(&output.proof);
+        assert!(!verify(&negated.to_bytes()));
+
+        let mut bytes = output.to_bytes();
+        bytes.pop();
+        assert!(matches!(verify_output_bytes(&bytes), Err(VerifyError::Malformed(_))));
+        assert!(!verify(&[]));
+    }
+}
@@ -0,0 +1,151 @@
+//! Wesolowski and Pietrzak verification in RSA-2048
+//!
+//! Follows `zkgame_vdf::wesolowski::verify_in_group` and
+//! `zkgame_vdf::pietrzak::verify_in_group` with `BigUintRsaGroup`; the
+//! modulus, challenges and parameter sets come from `zkgame_vdf_core`.
+
+use alloc::vec::Vec;
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use zkgame_vdf_core::encoding::DecodeError;
+use zkgame_vdf_core::hash_to_prime::hash_to_prime_with_counter;
+use zkgame_vdf_core::params::published_set;
+use zkgame_vdf_core::pietrzak::{folding_challenge, TAIL_ITERATIONS};
+use zkgame_vdf_core::{challenge_context, Scheme, Statement, DEFAULT_MAX_ITERATIONS, ELEMENT_BYTES, RSA_2048_MODULUS};
+
+use crate::VerifyError;
+
+struct RsaGroup {
+    modulus: BigUint,
+    quotient: bool,
+}
+
+impl RsaGroup {
+    fn rsa_2048(quotient: bool) -> Self {
+        Self {
+            modulus: BigUint::parse_bytes(RSA_2048_MODULUS.as_bytes(), 10).expect("Failed to parse RSA-2048 modulus"),
+            quotient,
+        }
+    }
+
+    /// Canonical representative `min(x, N - x)` in the quotient group `{±1}`,
+    /// or `x mod N` with plain residues
+    fn canonical(&self, element: BigUint) -> BigUint {
+        let reduced = element % &self.modulus;
+        if !self.quotient {
+            return reduced;
+        }
+        let negated = &self.modulus - &reduced;
+        if negated < reduced { negated } else { reduced }
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.canonical(a * b)
+    }
+
+    fn exp(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        self.canonical(base.modpow(exponent, &self.modulus))
+    }
+
+    fn encode(&self, element: &BigUint) -> [u8; ELEMENT_BYTES] {
+        let digits = element.to_bytes_be();
+        let mut bytes = [0u8; ELEMENT_BYTES];
+        bytes[ELEMENT_BYTES - digits.len()..].copy_from_slice(&digits);
+        bytes
+    }
+
+    /// `1 < x <= (N - 1) / 2`, or `0 < x < N` with plain residues, see
+    /// `zkgame_vdf::rsa_group`
+    fn is_valid(&self, element: &BigUint) -> bool {
+        if !self.quotient {
+            return !element.is_zero() && *element < self.modulus;
+        }
+        *element > BigUint::one() && *element <= &self.modulus >> 1
+    }
+}
+
+/// Check the proof of a decoded statement
+///
+/// Returns `Ok(false)` for well-formed statements that do not verify,
+/// including elements that are not canonical or are `±1`, apart from a
+/// Wesolowski proof of `1`.
+pub fn verify_statement(statement: &Statement) -> Result<bool, VerifyError> {
+    if statement.iterations > DEFAULT_MAX_ITERATIONS {
+        return Err(VerifyError::TooManyIterations);
+    }
+    if statement.output.len() != ELEMENT_BYTES {
+        return Err(VerifyError::Malformed(DecodeError::Malformed("Output is not one group element")));
+    }
+    if !statement.proof.len().is_multiple_of(ELEMENT_BYTES) {
+        return Err(VerifyError::Malformed(DecodeError::Malformed("Proof is not a list of group elements")));
+    }
+
+    let parameters = published_set(&statement.parameters).ok_or(VerifyError::UnknownParameters)?;
+    let group = RsaGroup::rsa_2048(parameters.quotient_group());
+    let input = group.canonical(BigUint::from_bytes_le(&statement.input.seed_digest()));
+    if !group.is_valid(&input) {
+        return Ok(false);
+    }
+    let output = BigUint::from_bytes_be(&statement.output);
+    let proof: Vec<BigUint> = statement.proof.chunks(ELEMENT_BYTES).map(BigUint::from_bytes_be).collect();
+    if !group.is_valid(&output) {
+        return Ok(false);
+    }
+
+    let context = challenge_context(parameters.proof_version, statement.scheme, &statement.input.action_type);
+    match (statement.scheme, proof.as_slice()) {
+        // The honest proof is 1 when 2^T < l, see `zkgame_vdf::wesolowski::is_valid_proof`
+        (Scheme::Wesolowski, [proof]) if !group.is_valid(proof) && !proof.is_one() => Ok(false),
+        (Scheme::Wesolowski, [proof]) => wesolowski(&group, &context, &input, &output, statement.iterations, proof),
+        (Scheme::Wesolowski, _) => Ok(false),
+        (Scheme::Pietrzak, proof) if !proof.iter().all(|element| group.is_valid(element)) => Ok(false),
+        (Scheme::Pietrzak, proof) => pietrzak(&group, &context, &input, &output, statement.iterations, proof),
+    }
+}
+
+/// `input^r × pi^l = output` with `l` the challenge prime and `r = 2^T mod l`
+fn wesolowski(group: &RsaGroup, context: &[u8], input: &BigUint, output: &BigUint, iterations: u64, proof: &BigUint) -> Result<bool, VerifyError> {
+    let (_, challenge) = hash_to_prime_with_counter(
+        &group.encode(&group.modulus),
+        iterations,
+        context,
+        &group.encode(input),
+        &group.encode(output),
+    ).ok_or(VerifyError::NoChallengePrime)?;
+    let remainder = BigUint::from(2u32).modpow(&BigUint::from(iterations), &challenge);
+    let left_side = group.mul(&group.exp(input, &remainder), &group.exp(proof, &challenge));
+    Ok(left_side == *output)
+}
+
+/// Fold the statement with each midpoint until it is short enough to square out
+fn pietrzak(group: &RsaGroup, context: &[u8], input: &BigUint, output: &BigUint, iterations: u64, proof: &[BigUint]) -> Result<bool, VerifyError> {
+    let mut x = input.clone();
+    let mut y = output.clone();
+    let mut t = iterations;
+
+    let mut midpoints = proof.iter();
+    while t > TAIL_ITERATIONS {
+        let mu = match midpoints.next() {
+            Some(mu) => mu,
+            None => return Ok(false),
+        };
+        if t % 2 == 1 {
+            y = group.mul(&y, &y);
+            t += 1;
+        }
+
+        let r = folding_challenge(context, &group.encode(&x), &group.encode(&y), &group.encode(mu), t);
+        x = group.mul(&group.exp(&x, &r), mu);
+        y = group.mul(&group.exp(mu, &r), &y);
+        t /= 2;
+    }
+    if midpoints.next().is_some() {
+        return Ok(false);
+    }
+
+    for _ in 0..t {
+        x = group.mul(&x, &x);
+    }
+    Ok(x == y)
+}
@@ -0,0 +1,141 @@
+//! Prover benchmarks: squaring throughput per backend and proof cost vs. T
+//!
+//! Run with `cargo bench --bench prover`. Save a baseline before changing a
+//! backend or the checkpoint strategy and compare against it afterwards:
+//!
+//! ```text
+//! cargo bench -- --save-baseline main
+//! cargo bench -- --baseline main
+//! ```
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zkgame_vdf::class_group::DEFAULT_DISCRIMINANT_BITS;
+use zkgame_vdf::{
+    challenge_context, BigUintRsaGroup, ClassGroup, SchemeKind, SquaringTrace, VDFEngine, VDFInput, VdfGroup,
+};
+
+/// Squarings per measured iteration of the throughput benchmarks
+const SQUARINGS: u64 = 1000;
+
+/// Delays the proof benchmarks are run at
+const PROOF_ITERATIONS: [u64; 3] = [1 << 12, 1 << 14, 1 << 16];
+
+fn input() -> VDFInput {
+    VDFInput {
+        player_id: 1,
+        action_type: "bench".to_string(),
+        action_id: 1,
+        timestamp: 1234567890,
+        nonce: 1,
+        random_salt: 12345,
+    }
+}
+
+fn squaring(c: &mut Criterion) {
+    let mut group = c.benchmark_group("squaring");
+    group.throughput(Throughput::Elements(SQUARINGS));
+
+    let engine = VDFEngine::new();
+    let modulus = engine.group().modulus().clone();
+    let seed = engine.generate_input_seed(&input());
+    group.bench_function("rsa_2048_gmp", |b| {
+        b.iter(|| {
+            let mut value = seed.clone();
+            for _ in 0..SQUARINGS {
+                value = value.square() % &modulus;
+            }
+            black_box(value)
+        })
+    });
+
+    let rsa = BigUintRsaGroup::rsa_2048();
+    let element = rsa.element_from_seed(&[7; 32]).expect("Seed is below the modulus");
+    group.bench_function("rsa_2048_num_bigint", |b| {
+        b.iter(|| {
+            let mut value = element.clone();
+            for _ in 0..SQUARINGS {
+                value = rsa.square(&value);
+            }
+            black_box(value)
+        })
+    });
+
+    let class_group = ClassGroup::from_seed(b"bench", DEFAULT_DISCRIMINANT_BITS);
+    let form = class_group.element_from_seed(&[7; 32]).expect("Seed maps to a form");
+    group.bench_function("class_group_1024", |b| {
+        b.iter(|| {
+            let mut value = form.clone();
+            for _ in 0..SQUARINGS {
+                value = class_group.square(&value);
+            }
+            black_box(value)
+        })
+    });
+
+    group.finish();
+}
+
+/// Proof construction alone, from a trace recorded up front
+fn prove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prove");
+    group.sample_size(10);
+
+    let engine = VDFEngine::new();
+    let modulus = engine.group().modulus().clone();
+    let input_seed = engine.generate_input_seed(&input());
+    for iterations in PROOF_ITERATIONS {
+        let mut trace = SquaringTrace::new(engine.checkpoint_plan(iterations));
+        let mut output = input_seed.clone();
+        for i in 0..iterations {
+            trace.observe(i, &output);
+            output = output.square() % &modulus;
+        }
+
+        group.throughput(Throughput::Elements(iterations));
+        for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+            let context = challenge_context(scheme, "bench");
+            let name = format!("{:?}", scheme).to_lowercase();
+            group.bench_with_input(BenchmarkId::new(name, iterations), &iterations, |b, _| {
+                b.iter(|| scheme.scheme().prove(&modulus, &context, &trace, &input_seed, &output).expect("Proof failed"))
+            });
+        }
+
+        let context = challenge_context(SchemeKind::Wesolowski, "bench");
+        for threads in [2, 4] {
+            let name = format!("wesolowski_{}_threads", threads);
+            group.bench_with_input(BenchmarkId::new(name, iterations), &iterations, |b, _| {
+                b.iter(|| {
+                    SchemeKind::Wesolowski.scheme()
+                        .prove_parallel(&modulus, &context, &trace, &input_seed, &output, threads)
+                        .expect("Proof failed")
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+/// Squaring plus proof, as run by `VDFEngine::compute`
+fn compute(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute");
+    group.sample_size(10);
+
+    for iterations in PROOF_ITERATIONS {
+        group.throughput(Throughput::Elements(iterations));
+        for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+            let engine = VDFEngine::new().with_scheme(scheme);
+            let name = format!("{:?}", scheme).to_lowercase();
+            group.bench_with_input(BenchmarkId::new(name, iterations), &iterations, |b, &iterations| {
+                b.iter(|| engine.compute(&input(), iterations).expect("VDF computation failed"))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, squaring, prove, compute);
+criterion_main!(benches);
@@ -0,0 +1,114 @@
+//! Verifier benchmarks: verification latency, hash-to-prime, batch
+//! verification and output serialization
+//!
+//! Run with `cargo bench --bench verifier`; see `benches/prover.rs` for
+//! saving and comparing baselines.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zkgame_vdf::{
+    challenge_context, hash_to_prime, verify_output_in, BigUintRsaGroup, SchemeKind, VDFEngine, VDFInput, VDFOutput, VdfGroup,
+};
+
+/// Delay of the outputs checked by the latency benchmarks
+const VERIFY_ITERATIONS: u64 = 1 << 14;
+
+/// Outputs per batch in the batch verification benchmark
+const BATCH_SIZES: [usize; 2] = [4, 16];
+
+fn input(action_id: u64) -> VDFInput {
+    VDFInput {
+        player_id: 1,
+        action_type: "bench".to_string(),
+        action_id,
+        timestamp: 1234567890,
+        nonce: 1,
+        random_salt: 12345,
+    }
+}
+
+fn output(scheme: SchemeKind, action_id: u64, iterations: u64) -> VDFOutput {
+    VDFEngine::new()
+        .with_scheme(scheme)
+        .compute(&input(action_id), iterations)
+        .expect("VDF computation failed")
+}
+
+fn verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify");
+    let engine = VDFEngine::new();
+    let rsa = BigUintRsaGroup::rsa_2048();
+
+    for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+        let output = output(scheme, 1, VERIFY_ITERATIONS);
+        let name = format!("{:?}", scheme).to_lowercase();
+        group.bench_function(BenchmarkId::new("gmp", &name), |b| {
+            b.iter(|| assert!(engine.verify_output(black_box(&output)).expect("Verification failed")))
+        });
+        group.bench_function(BenchmarkId::new("num_bigint", &name), |b| {
+            b.iter(|| assert!(verify_output_in(&rsa, black_box(&output)).expect("Verification failed")))
+        });
+    }
+
+    group.finish();
+}
+
+fn challenge(c: &mut Criterion) {
+    let output = output(SchemeKind::Wesolowski, 1, 1000);
+    let rsa = BigUintRsaGroup::rsa_2048();
+    let description = rsa.description();
+    let input = rsa.encode(&rsa.element_from_seed(&[7; 32]).expect("Seed is below the modulus")).expect("Encoding failed");
+    let context = challenge_context(SchemeKind::Wesolowski, "bench");
+
+    c.bench_function("hash_to_prime", |b| {
+        b.iter(|| hash_to_prime(&description, black_box(output.iterations), &context, &input, &output.output).expect("Hash to prime failed"))
+    });
+}
+
+fn batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_batch");
+    let engine = VDFEngine::new();
+
+    for size in BATCH_SIZES {
+        let outputs: Vec<VDFOutput> = (0..size as u64)
+            .map(|action_id| output(SchemeKind::Wesolowski, action_id, 1000))
+            .collect();
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("batched", size), &outputs, |b, outputs| {
+            b.iter(|| engine.verify_batch(outputs))
+        });
+        group.bench_with_input(BenchmarkId::new("one_by_one", size), &outputs, |b, outputs| {
+            b.iter(|| outputs.iter().map(|output| engine.verify_output(output)).collect::<Vec<_>>())
+        });
+    }
+
+    group.finish();
+}
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("serialization");
+
+    for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+        let output = output(scheme, 1, VERIFY_ITERATIONS);
+        let name = format!("{:?}", scheme).to_lowercase();
+        let bytes = output.to_bytes();
+        let json = serde_json::to_string(&output).expect("Serialization failed");
+
+        group.bench_function(BenchmarkId::new("to_bytes", &name), |b| b.iter(|| black_box(&output).to_bytes()));
+        group.bench_function(BenchmarkId::new("from_bytes", &name), |b| {
+            b.iter(|| VDFOutput::from_bytes(black_box(&bytes)).expect("Decoding failed"))
+        });
+        group.bench_function(BenchmarkId::new("to_json", &name), |b| {
+            b.iter(|| serde_json::to_string(black_box(&output)).expect("Serialization failed"))
+        });
+        group.bench_function(BenchmarkId::new("from_json", &name), |b| {
+            b.iter(|| serde_json::from_str::<VDFOutput>(black_box(&json)).expect("Deserialization failed"))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, verify, challenge, batch, serialization);
+criterion_main!(benches);
@@ -0,0 +1,10 @@
+//! Print the Solidity verifier for the RSA-2048 group used by `VDFEngine`
+//!
+//! `cargo run --example solidity_verifier > ../contracts/VDFVerifier.sol`
+
+use zkgame_vdf::solidity::verifier_source;
+use zkgame_vdf::BigUintRsaGroup;
+
+fn main() {
+    print!("{}", verifier_source(&BigUintRsaGroup::rsa_2048()));
+}
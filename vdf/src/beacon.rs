@@ -0,0 +1,292 @@
+//! Randomness beacon for loot and resource rolls
+//!
+//! A beacon runs the VDF on a public epoch seed, such as a block hash or the
+//! output of the previous beacon, and derives random values from the output.
+//! The output takes the full delay to compute and there is exactly one valid
+//! output per seed, so nobody can grind the values.
+//!
+//! The values are only unpredictable if the seed is. A block hash or an
+//! earlier beacon's output (`EpochSeed::Beacon`, see `Beacon::next_seed`)
+//! becomes known at a fixed point, so nobody learns the values before that
+//! point plus the delay. A plain epoch number (`EpochSeed::Epoch`) is known
+//! long in advance: anyone can evaluate the beacon for a future epoch today,
+//! so epoch seeds give no unpredictability at all and only suit tests and
+//! values that may be public ahead of time. `EpochSeed::is_unpredictable`
+//! tells the two apart.
+//!
+//! The seed enters the squaring chain through an ordinary `VDFInput`
+//! (`beacon_input`), so a beacon proof is a `VDFOutput` checked with
+//! `verify_output_in`. Random values must only be used from a beacon that
+//! verified:
+//!
+//! ```text
+//! R      = SHA-256(RANDOMNESS_TAG || y)
+//! v(l,i) = SHA-256(RANDOMNESS_TAG || R || u32(len(l)) || l || u64(i) || u32(draw))
+//! ```
+//!
+//! where `y` is the 256-byte output encoding, read in the group of the
+//! output's parameter set. Outputs of the current set are canonical
+//! representatives in the quotient group `{±1}` (see `rsa_group`), so each
+//! seed has a single encoded output and a single `R`.
+
+use serde::{Serialize, Deserialize};
+use sha2::{Sha256, Digest};
+
+use crate::error::VdfError;
+use crate::group::{verify_output_in, VdfGroup};
+use crate::{VDFInput, VDFOutput};
+
+/// Action type prefix of beacon inputs
+pub const BEACON_ACTION_TYPE: &str = "beacon";
+
+/// Domain separation tag for beacon randomness
+pub const RANDOMNESS_TAG: &[u8] = b"zkgame-vdf-beacon-v1";
+
+/// Public value a beacon is evaluated on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EpochSeed {
+    /// `StateManager.globalEpoch`; predictable, see the module documentation
+    Epoch(u64),
+    /// Hash of a block produced after the beacon was requested
+    BlockHash(#[serde(with = "crate::encoding::hex_bytes")] Vec<u8>),
+    /// Value `R` of the previous epoch's verified beacon
+    Beacon(#[serde(with = "crate::encoding::hex_bytes")] Vec<u8>),
+}
+
+impl EpochSeed {
+    /// Whether the seed only becomes known once it is public, so that a
+    /// delay run on it cannot have started earlier
+    pub fn is_unpredictable(&self) -> bool {
+        match self {
+            EpochSeed::Epoch(_) => false,
+            EpochSeed::BlockHash(_) | EpochSeed::Beacon(_) => true,
+        }
+    }
+
+    /// `0x00 | u64 epoch`, `0x01 | block hash` or `0x02 | beacon value`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            EpochSeed::Epoch(epoch) => {
+                let mut bytes = vec![0];
+                bytes.extend_from_slice(&epoch.to_be_bytes());
+                bytes
+            }
+            EpochSeed::BlockHash(hash) => {
+                let mut bytes = vec![1];
+                bytes.extend_from_slice(hash);
+                bytes
+            }
+            EpochSeed::Beacon(value) => {
+                let mut bytes = vec![2];
+                bytes.extend_from_slice(value);
+                bytes
+            }
+        }
+    }
+
+    /// Decode the encoding written by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VdfError> {
+        match bytes.split_first() {
+            Some((0, epoch)) => {
+                let epoch: [u8; 8] = epoch.try_into()
+                    .map_err(|_| VdfError::MalformedProof("Epoch seed must hold 8 bytes".to_string()))?;
+                Ok(EpochSeed::Epoch(u64::from_be_bytes(epoch)))
+            }
+            Some((1, hash)) => Ok(EpochSeed::BlockHash(hash.to_vec())),
+            Some((2, value)) => Ok(EpochSeed::Beacon(value.to_vec())),
+            _ => Err(VdfError::MalformedProof("Unknown epoch seed kind".to_string())),
+        }
+    }
+}
+
+/// The VDF input a beacon squares: only the action type carries the seed
+pub fn beacon_input(seed: &EpochSeed) -> VDFInput {
+    VDFInput {
+        player_id: 0,
+        action_type: format!("{}:{}", BEACON_ACTION_TYPE, hex::encode(seed.to_bytes())),
+        action_id: 0,
+        timestamp: 0,
+        nonce: 0,
+        random_salt: 0,
+    }
+}
+
+/// A beacon evaluation and its proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Beacon {
+    pub seed: EpochSeed,
+    pub output: VDFOutput,
+}
+
+impl Beacon {
+    /// Check that the output was computed on this beacon's seed and that its proof verifies in `group`
+    pub fn verify_in<G: VdfGroup>(&self, group: &G) -> Result<bool, VdfError> {
+        if self.output.input != beacon_input(&self.seed) {
+            return Ok(false);
+        }
+        verify_output_in(group, &self.output)
+    }
+
+    /// 32-byte beacon value `R`, see the module documentation
+    ///
+    /// The output is read in the group of its recorded parameter set, the
+    /// same one the beacon verifies in.
+    pub fn randomness(&self) -> Result<[u8; 32], VdfError> {
+        let group = self.output.parameters.rsa_group()?;
+        let value = group.decode(&self.output.output)?;
+        if !group.is_valid(&value) {
+            return Err(VdfError::MalformedProof("Beacon output is not a canonical group element".to_string()));
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(RANDOMNESS_TAG);
+        hasher.update(group.encode(&value)?);
+        Ok(hasher.finalize().into())
+    }
+
+    /// Seed of the next epoch's beacon, chained to this beacon's value
+    ///
+    /// Only call this on a beacon that verified.
+    pub fn next_seed(&self) -> Result<EpochSeed, VdfError> {
+        Ok(EpochSeed::Beacon(self.randomness()?.to_vec()))
+    }
+
+    /// Independent 32-byte value for the purpose `label` and the roll `index`
+    pub fn random_bytes(&self, label: &str, index: u64) -> Result<[u8; 32], VdfError> {
+        Ok(draw(&self.randomness()?, label, index, 0))
+    }
+
+    /// Uniform value in `0..bound`, by rejection sampling so that no
+    /// remainder is favoured; `None` for an empty range
+    pub fn random_below(&self, label: &str, index: u64, bound: u64) -> Result<Option<u64>, VdfError> {
+        if bound == 0 {
+            return Ok(None);
+        }
+        let randomness = self.randomness()?;
+        // Largest multiple of `bound` that fits in 64 bits
+        let limit = (1u128 << 64) / bound as u128 * bound as u128;
+        for attempt in 0.. {
+            let digest = draw(&randomness, label, index, attempt);
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&digest[..8]);
+            let value = u64::from_be_bytes(word);
+            if (value as u128) < limit {
+                return Ok(Some(value % bound));
+            }
+        }
+        unreachable!("Rejection sampling accepts with probability above 1/2")
+    }
+
+    /// Whether an event with probability `numerator / denominator` happens
+    /// for the roll `index`, e.g. a rare drop
+    pub fn chance(&self, label: &str, index: u64, numerator: u64, denominator: u64) -> Result<bool, VdfError> {
+        Ok(self.random_below(label, index, denominator)?.is_some_and(|roll| roll < numerator))
+    }
+}
+
+fn draw(randomness: &[u8; 32], label: &str, index: u64, attempt: u32) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(RANDOMNESS_TAG);
+    hasher.update(randomness);
+    hasher.update((label.len() as u32).to_be_bytes());
+    hasher.update(label.as_bytes());
+    hasher.update(index.to_be_bytes());
+    hasher.update(attempt.to_be_bytes());
+    hasher.finalize().into()
+}
+
+#[cfg(all(test, feature = "gmp"))]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+    use crate::encoding::pad_element;
+    use crate::{BigUintRsaGroup, ParameterSet, VDFEngine};
+
+    /// `N - value` for an encoded element
+    fn negate(value: &[u8]) -> Vec<u8> {
+        let group = BigUintRsaGroup::rsa_2048();
+        pad_element(&(group.modulus() - BigUint::from_bytes_be(value)).to_bytes_be()).expect("Element fits").to_vec()
+    }
+
+    #[test]
+    fn test_beacon_round_trip() {
+        let engine = VDFEngine::new();
+        let beacon = engine.run_beacon(EpochSeed::Epoch(7), 500).expect("Beacon failed");
+        assert!(engine.verify_beacon(&beacon).expect("Verification failed"));
+        assert!(beacon.verify_in(&BigUintRsaGroup::rsa_2048()).expect("Verification failed"));
+
+        // Deterministic for a seed, different across seeds
+        let again = engine.run_beacon(EpochSeed::Epoch(7), 500).expect("Beacon failed");
+        assert_eq!(beacon.randomness(), again.randomness());
+        let other = engine.run_beacon(EpochSeed::BlockHash(vec![0xab; 32]), 500).expect("Beacon failed");
+        assert_ne!(beacon.randomness().expect("Randomness failed"), other.randomness().expect("Randomness failed"));
+
+        let json = serde_json::to_string(&other).expect("Serialization failed");
+        let decoded: Beacon = serde_json::from_str(&json).expect("Deserialization failed");
+        assert_eq!(decoded.seed, other.seed);
+        assert!(engine.verify_beacon(&decoded).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_beacon_is_bound_to_its_seed() {
+        let engine = VDFEngine::new();
+        let beacon = engine.run_beacon(EpochSeed::Epoch(7), 500).expect("Beacon failed");
+
+        let mut relabelled = beacon.clone();
+        relabelled.seed = EpochSeed::Epoch(8);
+        assert!(!engine.verify_beacon(&relabelled).expect("Verification failed"));
+
+        let mut reused = beacon.clone();
+        reused.output.input.player_id = 1;
+        assert!(!engine.verify_beacon(&reused).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_chained_seeds() {
+        let engine = VDFEngine::new();
+        let beacon = engine.run_beacon(EpochSeed::BlockHash(vec![0xab; 32]), 300).expect("Beacon failed");
+        let seed = beacon.next_seed().expect("Randomness failed");
+        assert!(seed.is_unpredictable());
+        assert!(!EpochSeed::Epoch(7).is_unpredictable());
+        assert_eq!(EpochSeed::from_bytes(&seed.to_bytes()).expect("Decoding failed"), seed);
+
+        let next = engine.run_beacon(seed, 300).expect("Beacon failed");
+        assert!(engine.verify_beacon(&next).expect("Verification failed"));
+        assert_ne!(next.randomness().expect("Randomness failed"), beacon.randomness().expect("Randomness failed"));
+    }
+
+    #[test]
+    fn test_negated_output_is_rejected() {
+        let engine = VDFEngine::new();
+        let beacon = engine.run_beacon(EpochSeed::Epoch(9), 400).expect("Beacon failed");
+
+        let mut negated = beacon.clone();
+        negated.output.output = negate(&beacon.output.output);
+        negated.output.proof = negate(&beacon.output.proof);
+        assert!(!engine.verify_beacon(&negated).expect("Verification failed"));
+        assert!(negated.randomness().is_err());
+
+        // Without the quotient group N - y is a distinct element, read as recorded
+        let mut legacy = negated.clone();
+        legacy.output.parameters = ParameterSet::legacy();
+        assert!(legacy.randomness().is_ok());
+        assert_ne!(legacy.randomness().expect("Randomness failed"), beacon.randomness().expect("Randomness failed"));
+    }
+
+    #[test]
+    fn test_random_below_stays_in_range() {
+        let engine = VDFEngine::new();
+        let beacon = engine.run_beacon(EpochSeed::Epoch(3), 300).expect("Beacon failed");
+        assert_eq!(beacon.random_below("loot", 0, 0).expect("Roll failed"), None);
+        for bound in [1, 2, 6, 1000, u64::MAX] {
+            for index in 0..20 {
+                let roll = beacon.random_below("loot", index, bound).expect("Roll failed").expect("Empty range");
+                assert!(roll < bound);
+            }
+        }
+        assert_ne!(beacon.random_bytes("loot", 0).expect("Roll failed"), beacon.random_bytes("gather", 0).expect("Roll failed"));
+        assert!(beacon.chance("drop", 0, 1, 1).expect("Roll failed"));
+        assert!(!beacon.chance("drop", 0, 0, 1).expect("Roll failed"));
+    }
+}
@@ -0,0 +1,183 @@
+//! Local machine calibration
+//!
+//! The number of squarings an action requires is fixed by the protocol: the
+//! `iterations_per_second` of the engine's parameter set times the action's
+//! nominal duration, shared with `config/circuits_config.json` and
+//! `circuits/timeCraft.circom`.
+//! How long those squarings take on a given machine is a separate,
+//! measured quantity. A `CalibrationProfile` records that measurement so the
+//! expected wall-clock time can be shown to the player, and flags actions
+//! whose local time is far from their nominal duration.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
+use crate::error::VdfError;
+use crate::params::ParameterSet;
+use crate::VDFEngine;
+
+/// Current calibration profile format version
+pub const CALIBRATION_VERSION: u32 = 1;
+
+/// Default number of squarings measured by `CalibrationProfile::measure`
+pub const DEFAULT_CALIBRATION_ITERATIONS: u64 = 20_000;
+
+/// Local and nominal durations differing by more than this factor raise a mismatch
+pub const MISMATCH_TOLERANCE: f64 = 2.0;
+
+/// Measured squaring speed of the local machine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationProfile {
+    pub version: u32,
+    /// Squarings per second, including proof generation
+    pub iterations_per_second: f64,
+    /// Squarings timed to obtain the rate
+    pub sample_iterations: u64,
+    /// Unix time of the measurement
+    pub measured_at: u64,
+}
+
+impl CalibrationProfile {
+    /// Time `sample_iterations` squarings with `engine` and record the rate
+    pub fn measure(engine: &VDFEngine, sample_iterations: u64) -> Result<Self, VdfError> {
+        let iterations_per_second = engine.benchmark(sample_iterations.max(1))?;
+        Ok(Self {
+            version: CALIBRATION_VERSION,
+            iterations_per_second,
+            sample_iterations: sample_iterations.max(1),
+            measured_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        })
+    }
+
+    /// Expected local wall-clock seconds for `iterations` squarings
+    pub fn expected_seconds(&self, iterations: u64) -> f64 {
+        iterations as f64 / self.iterations_per_second
+    }
+
+    /// Compare the local time for `iterations` against their nominal
+    /// duration at the protocol rate of `parameters`
+    pub fn check(&self, iterations: u64, parameters: &ParameterSet) -> Option<TimingMismatch> {
+        let nominal_seconds = iterations as f64 / parameters.iterations_per_second.max(1) as f64;
+        let local_seconds = self.expected_seconds(iterations);
+        if nominal_seconds == 0.0 {
+            return None;
+        }
+        let ratio = local_seconds / nominal_seconds;
+        if (1.0 / MISMATCH_TOLERANCE..=MISMATCH_TOLERANCE).contains(&ratio) {
+            return None;
+        }
+        Some(TimingMismatch { iterations, nominal_seconds, local_seconds })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), VdfError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| VdfError::InvalidCalibration(e.to_string()))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Read a profile written by `save`
+    ///
+    /// Only failing to read the file is an `Io` error; a file that is not a
+    /// profile of the current version returns `InvalidCalibration`.
+    pub fn load(path: &Path) -> Result<Self, VdfError> {
+        let profile: Self = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| VdfError::InvalidCalibration(e.to_string()))?;
+        if profile.version != CALIBRATION_VERSION {
+            return Err(VdfError::InvalidCalibration(format!("Unsupported calibration version {}", profile.version)));
+        }
+        if !profile.iterations_per_second.is_finite() || profile.iterations_per_second <= 0.0 {
+            return Err(VdfError::InvalidCalibration("Calibration rate must be positive".to_string()));
+        }
+        Ok(profile)
+    }
+}
+
+/// The local machine is much faster or slower than the protocol rate assumes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingMismatch {
+    pub iterations: u64,
+    /// Duration the protocol assigns to `iterations`
+    pub nominal_seconds: f64,
+    /// Expected duration on this machine
+    pub local_seconds: f64,
+}
+
+impl fmt::Display for TimingMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} iterations are nominally {:.0}s but will take about {:.0}s on this machine",
+            self.iterations, self.nominal_seconds, self.local_seconds
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(iterations_per_second: f64) -> CalibrationProfile {
+        CalibrationProfile {
+            version: CALIBRATION_VERSION,
+            iterations_per_second,
+            sample_iterations: 1000,
+            measured_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_mismatch_outside_tolerance() {
+        let parameters = ParameterSet::current();
+        let rate = parameters.iterations_per_second;
+        let iterations = 3600 * rate;
+        assert!(profile(rate as f64 * 1.5).check(iterations, &parameters).is_none());
+
+        let fast = profile(rate as f64 * 100.0).check(iterations, &parameters)
+            .expect("A fast machine should be flagged");
+        assert_eq!(fast.nominal_seconds, 3600.0);
+        assert!((fast.local_seconds - 36.0).abs() < 1e-9);
+
+        assert!(profile(rate as f64 / 3.0).check(iterations, &parameters).is_some());
+    }
+
+    #[test]
+    fn test_nominal_time_follows_the_parameter_set() {
+        let mut faster = ParameterSet::current();
+        faster.iterations_per_second *= 100;
+        let profile = profile(faster.iterations_per_second as f64);
+        let iterations = 3600 * faster.iterations_per_second;
+        assert!(profile.check(iterations, &faster).is_none());
+        assert!(profile.check(iterations, &ParameterSet::current()).is_some());
+    }
+
+    #[test]
+    fn test_load_rejects_other_formats() {
+        let path = std::env::temp_dir().join(format!("zkgame-vdf-{}-bad-calibration.json", std::process::id()));
+        let mut future = profile(1000.0);
+        future.version = CALIBRATION_VERSION + 1;
+        future.save(&path).expect("Failed to save calibration");
+        assert!(matches!(CalibrationProfile::load(&path), Err(VdfError::InvalidCalibration(_))));
+
+        std::fs::write(&path, "not json").expect("Failed to write calibration");
+        assert!(matches!(CalibrationProfile::load(&path), Err(VdfError::InvalidCalibration(_))));
+        std::fs::remove_file(&path).expect("Failed to remove calibration");
+
+        assert!(matches!(CalibrationProfile::load(&path), Err(VdfError::Io(_))));
+    }
+
+    #[test]
+    fn test_measure_and_reload() {
+        let measured = CalibrationProfile::measure(&VDFEngine::new(), 200).expect("Calibration failed");
+        assert!(measured.iterations_per_second > 0.0);
+
+        let path = std::env::temp_dir().join(format!("zkgame-vdf-{}-calibration.json", std::process::id()));
+        measured.save(&path).expect("Failed to save calibration");
+        assert_eq!(CalibrationProfile::load(&path).expect("Failed to load calibration"), measured);
+        std::fs::remove_file(&path).expect("Failed to remove calibration");
+    }
+}
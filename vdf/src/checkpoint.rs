@@ -0,0 +1,193 @@
+//! Checkpointed squaring traces for single-pass proof generation
+//!
+//! While `VDFEngine::compute` squares, it keeps every `interval`-th
+//! intermediate value. The Wesolowski prover then rebuilds
+//! `pi = x^floor(2^T / l)` from those checkpoints instead of redoing the
+//! whole delay (Wesolowski, "Efficient verifiable delay functions", §4.1).
+//!
+//! The quotient `floor(2^T / l)` is split into `window_bits`-bit digits.
+//! Digits that share a value are multiplied together first, so the proof
+//! costs roughly `T / window_bits` group multiplications plus a small
+//! per-window overhead, instead of `T` squarings.
+//!
+//! With several prover threads the checkpoints are split into contiguous
+//! shards that are accumulated independently, see
+//! `VDFEngine::with_prover_threads`.
+
+use rug::Integer;
+
+use crate::encoding::ELEMENT_BYTES;
+
+/// Default memory budget for checkpoints and proof buckets (64 MiB)
+pub const DEFAULT_MEMORY_BUDGET: usize = 64 * 1024 * 1024;
+
+/// Upper bound on the digit width, keeps the bucket table small
+pub(crate) const MAX_WINDOW_BITS: u32 = 16;
+
+/// Checkpointing options for VDF computation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointConfig {
+    /// Keep one intermediate value every `interval` squarings.
+    /// When `None`, the densest interval that fits the memory budget is used.
+    pub interval: Option<u64>,
+    /// Upper bound in bytes for checkpoints plus proof buckets
+    pub memory_budget: usize,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            interval: None,
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+        }
+    }
+}
+
+/// Checkpoint layout chosen for a given iteration count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointPlan {
+    /// Total number of squarings
+    pub iterations: u64,
+    /// Width in bits of each quotient digit
+    pub window_bits: u32,
+    /// Number of digits between two stored checkpoints
+    pub stride: u64,
+    /// Squarings between two stored checkpoints (`window_bits * stride`)
+    pub interval: u64,
+}
+
+impl CheckpointPlan {
+    /// Pick the cheapest digit width and stride for `iterations` under `config`
+    pub fn new(iterations: u64, config: &CheckpointConfig) -> Self {
+        // Half of the budget goes to checkpoints, half to the bucket table
+        let half_budget = (config.memory_budget / 2).max(ELEMENT_BYTES);
+        let max_checkpoints = (half_budget / ELEMENT_BYTES) as u64;
+        let mut max_window = 1;
+        while max_window < MAX_WINDOW_BITS && (ELEMENT_BYTES << (max_window + 1)) <= half_budget {
+            max_window += 1;
+        }
+
+        let min_interval = match config.interval {
+            Some(interval) => interval.max(1),
+            None => iterations.div_ceil(max_checkpoints).max(1),
+        };
+
+        let mut best: Option<(u128, CheckpointPlan)> = None;
+        for window_bits in 1..=max_window {
+            let kappa = window_bits as u64;
+            // An explicit interval is honoured exactly, so the digit width must divide it
+            if config.interval.is_some() && !min_interval.is_multiple_of(kappa) {
+                continue;
+            }
+            let stride = min_interval.div_ceil(kappa);
+            let cost = (iterations / kappa) as u128
+                + (stride as u128) * (1u128 << (window_bits + 1))
+                + (kappa * stride) as u128;
+
+            let plan = CheckpointPlan {
+                iterations,
+                window_bits,
+                stride,
+                interval: kappa * stride,
+            };
+            if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+                best = Some((cost, plan));
+            }
+        }
+
+        // window_bits = 1 always divides the interval, so a plan always exists
+        best.expect("No checkpoint plan available").1
+    }
+
+    /// Number of checkpoints stored while squaring
+    pub fn checkpoint_count(&self) -> u64 {
+        self.iterations.div_ceil(self.interval)
+    }
+
+    /// Number of quotient digits the prover consumes
+    pub fn digit_count(&self) -> u64 {
+        self.iterations.div_ceil(self.window_bits as u64)
+    }
+
+    /// Peak memory in bytes used by checkpoints and proof buckets
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage_with_threads(1)
+    }
+
+    /// Peak memory when the proof is built by `threads` workers, each with
+    /// its own bucket table
+    pub fn memory_usage_with_threads(&self, threads: usize) -> usize {
+        let checkpoints = self.checkpoint_count() as usize * ELEMENT_BYTES;
+        let buckets = ELEMENT_BYTES << self.window_bits;
+        let workers = (threads as u64).clamp(1, self.checkpoint_count().max(1)) as usize;
+        checkpoints + buckets * workers
+    }
+}
+
+/// Intermediate values `x^(2^(i * interval))` recorded during squaring
+#[derive(Debug, Clone)]
+pub struct SquaringTrace {
+    plan: CheckpointPlan,
+    checkpoints: Vec<Integer>,
+}
+
+impl SquaringTrace {
+    /// Create an empty trace for the given plan
+    pub fn new(plan: CheckpointPlan) -> Self {
+        Self {
+            plan,
+            checkpoints: Vec::with_capacity(plan.checkpoint_count() as usize),
+        }
+    }
+
+    /// Rebuild a trace from checkpoints recorded earlier
+    pub(crate) fn from_parts(plan: CheckpointPlan, checkpoints: Vec<Integer>) -> Self {
+        Self { plan, checkpoints }
+    }
+
+    /// Record `value` if `iteration` falls on a checkpoint boundary
+    pub fn observe(&mut self, iteration: u64, value: &Integer) {
+        if iteration.is_multiple_of(self.plan.interval) && iteration < self.plan.iterations {
+            self.checkpoints.push(value.clone());
+        }
+    }
+
+    /// Layout this trace was recorded with
+    pub fn plan(&self) -> &CheckpointPlan {
+        &self.plan
+    }
+
+    /// Recorded checkpoints, `checkpoints()[i] = x^(2^(i * interval))`
+    pub fn checkpoints(&self) -> &[Integer] {
+        &self.checkpoints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_respects_explicit_interval() {
+        let config = CheckpointConfig {
+            interval: Some(12),
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+        };
+        let plan = CheckpointPlan::new(1000, &config);
+        assert_eq!(plan.interval, 12);
+        assert_eq!(plan.interval, plan.window_bits as u64 * plan.stride);
+        assert_eq!(plan.checkpoint_count(), 84);
+    }
+
+    #[test]
+    fn test_plan_fits_memory_budget() {
+        let config = CheckpointConfig {
+            interval: None,
+            memory_budget: 1024 * 1024,
+        };
+        let iterations = 3600 * 1_000_000;
+        let plan = CheckpointPlan::new(iterations, &config);
+        assert!(plan.memory_usage() <= config.memory_budget);
+        assert!(plan.checkpoint_count() * plan.interval >= iterations);
+    }
+}
@@ -0,0 +1,547 @@
+//! Class group of an imaginary quadratic order as a VDF group
+//!
+//! The RSA-2048 backend trusts that nobody kept the factorization of the
+//! modulus. A class group has unknown order without any trusted setup: the
+//! discriminant `D = -p` is derived from a public seed, so a server can start
+//! a fresh group for every season by publishing a new seed.
+//!
+//! Elements are reduced binary quadratic forms `(a, b, c)` with
+//! `b^2 - 4ac = D`. Only `a` and `b` are stored in a `VDFOutput`; `c` follows
+//! from the discriminant. A reduced form has `|b| <= a < sqrt(|D|)`, so both
+//! fit in half the discriminant's width: the encoding is `a` big-endian, a
+//! sign byte for `b`, then `|b|` big-endian, each coefficient padded to
+//! `ceil(bits / 16)` bytes.
+
+use std::fmt;
+use std::time::SystemTime;
+
+use rug::Integer;
+use rug::integer::{IsPrime, Order};
+use sha2::{Sha256, Digest};
+
+use crate::checkpoint::{CheckpointConfig, CheckpointPlan};
+use crate::error::VdfError;
+use crate::group::{verify_output_in, VdfGroup};
+use crate::params::ParameterSet;
+use crate::rsa_group::to_integer;
+use crate::scheme::SchemeKind;
+use crate::wesolowski::{challenge_in, verify_in_group};
+use crate::{input_seed, VDFInput, VDFOutput};
+
+/// Default discriminant size in bits
+pub const DEFAULT_DISCRIMINANT_BITS: u32 = 1024;
+
+/// Domain tag mixed into the discriminant derivation
+const DISCRIMINANT_TAG: &[u8] = b"zkgame-class-group-discriminant";
+
+/// Reduced binary quadratic form `a x^2 + b xy + c y^2`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuadraticForm {
+    a: Integer,
+    b: Integer,
+    c: Integer,
+}
+
+impl QuadraticForm {
+    pub fn a(&self) -> &Integer {
+        &self.a
+    }
+
+    pub fn b(&self) -> &Integer {
+        &self.b
+    }
+
+    pub fn c(&self) -> &Integer {
+        &self.c
+    }
+
+    /// Move `b` into `(-a, a]` without changing the class
+    fn normalize(&mut self) {
+        // r = floor((a - b) / 2a), b' = b + 2ra, c' = ar^2 + br + c
+        let (r, _) = (self.a.clone() - &self.b).div_rem_floor(self.a.clone() * 2u32);
+        let ar = self.a.clone() * &r;
+        self.c += (ar.clone() + &self.b) * &r;
+        self.b += ar * 2u32;
+    }
+
+    /// Reduce to the unique representative with `|b| <= a <= c`
+    fn reduce(mut self) -> Self {
+        self.normalize();
+        while self.a > self.c || (self.a == self.c && self.b < 0) {
+            // s = floor((c + b) / 2c), (a, b, c) -> (c, 2sc - b, cs^2 - bs + a)
+            let (s, _) = (self.c.clone() + &self.b).div_rem_floor(self.c.clone() * 2u32);
+            let cs = self.c.clone() * &s;
+            let c = (cs.clone() - &self.b) * &s + &self.a;
+            self.b = cs * 2u32 - &self.b;
+            self.a = std::mem::replace(&mut self.c, c);
+        }
+        self.normalize();
+        self
+    }
+}
+
+impl fmt::Display for QuadraticForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.a, self.b)
+    }
+}
+
+/// Class group of discriminant `D = -p` with `p ≡ 3 (mod 4)` prime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassGroup {
+    discriminant: Integer,
+}
+
+impl ClassGroup {
+    /// Derive a `bits`-bit discriminant from a public seed
+    pub fn from_seed(seed: &[u8], bits: u32) -> Self {
+        // Expand the seed with SHA-256 in counter mode
+        let mut expanded = Vec::new();
+        let mut counter = 0u32;
+        while expanded.len() * 8 < bits as usize {
+            let mut hasher = Sha256::new();
+            hasher.update(DISCRIMINANT_TAG);
+            hasher.update(seed);
+            hasher.update(counter.to_be_bytes());
+            expanded.extend_from_slice(&hasher.finalize());
+            counter += 1;
+        }
+
+        // Top bit set for an exact size, low bits 11 for p ≡ 3 (mod 4)
+        let mut p = Integer::from_digits(&expanded, Order::Msf);
+        p >>= (expanded.len() * 8) as u32 - bits;
+        p |= Integer::from(1) << (bits - 1);
+        p |= 3u32;
+        while p.is_probably_prime(30) == IsPrime::No {
+            p += 4;
+        }
+
+        Self { discriminant: -p }
+    }
+
+    pub fn discriminant(&self) -> &Integer {
+        &self.discriminant
+    }
+
+    /// SHA-256 of the discriminant's big-endian magnitude, identifying the
+    /// group in its `ParameterSet`
+    pub fn digest(&self) -> [u8; 32] {
+        Sha256::digest(self.description()).into()
+    }
+
+    /// Build the reduced form with coefficients `a` and `b`, checking that it
+    /// belongs to this group
+    pub fn form(&self, a: Integer, b: Integer) -> Result<QuadraticForm, VdfError> {
+        if a <= 0 {
+            return Err(VdfError::MalformedProof("Form coefficient a must be positive".to_string()));
+        }
+        let numerator = b.clone().square() - &self.discriminant;
+        let denominator = a.clone() * 4u32;
+        if !numerator.is_divisible(&denominator) {
+            return Err(VdfError::MalformedProof("Form does not match the group discriminant".to_string()));
+        }
+        let c = numerator / denominator;
+
+        let form = QuadraticForm { a, b, c };
+        if form.clone().reduce() != form {
+            return Err(VdfError::MalformedProof("Form is not reduced".to_string()));
+        }
+        Ok(form)
+    }
+
+    /// Bytes per coefficient in the fixed-width form encoding
+    fn coefficient_bytes(&self) -> usize {
+        (self.discriminant.significant_bits() as usize).div_ceil(16)
+    }
+
+    /// Fixed-width encoding `a || sign(b) || |b|`
+    pub fn encode_form(&self, form: &QuadraticForm) -> Result<Vec<u8>, VdfError> {
+        let width = self.coefficient_bytes();
+        let mut bytes = Vec::with_capacity(2 * width + 1);
+        for (index, coefficient) in [&form.a, &form.b].into_iter().enumerate() {
+            if index == 1 {
+                bytes.push(u8::from(*coefficient < 0));
+            }
+            let digits = coefficient.to_digits::<u8>(Order::Msf);
+            if digits.len() > width {
+                return Err(VdfError::MalformedProof("Form coefficient is wider than the discriminant allows".to_string()));
+            }
+            bytes.resize(bytes.len() + width - digits.len(), 0);
+            bytes.extend_from_slice(&digits);
+        }
+        Ok(bytes)
+    }
+
+    /// Decode a form written by `encode_form`, checking that it belongs to this group
+    pub fn decode_form(&self, bytes: &[u8]) -> Result<QuadraticForm, VdfError> {
+        let width = self.coefficient_bytes();
+        if bytes.len() != 2 * width + 1 {
+            return Err(VdfError::MalformedProof(format!(
+                "Form must be {} bytes, got {}",
+                2 * width + 1,
+                bytes.len()
+            )));
+        }
+        let a = Integer::from_digits(&bytes[..width], Order::Msf);
+        let mut b = Integer::from_digits(&bytes[width + 1..], Order::Msf);
+        match bytes[width] {
+            0 => {}
+            1 => b = -b,
+            _ => return Err(VdfError::MalformedProof("Invalid sign byte in form".to_string())),
+        }
+        self.form(a, b)
+    }
+
+    /// Group operation (Shanks composition, Cohen Algorithm 5.4.7)
+    pub fn compose(&self, f1: &QuadraticForm, f2: &QuadraticForm) -> QuadraticForm {
+        let (f1, f2) = if f1.a > f2.a { (f2, f1) } else { (f1, f2) };
+        let s = (f1.b.clone() + &f2.b) >> 1;
+        let n = f2.b.clone() - &s;
+
+        let (y1, d) = if f2.a.is_divisible(&f1.a) {
+            (Integer::new(), f1.a.clone())
+        } else {
+            let (d, u, _) = <(Integer, Integer, Integer)>::from(f2.a.extended_gcd_ref(&f1.a));
+            (u, d)
+        };
+        let (x2, y2, d1) = if s.is_divisible(&d) {
+            (Integer::new(), Integer::from(-1), d)
+        } else {
+            let (d1, x2, y2) = <(Integer, Integer, Integer)>::from(s.extended_gcd_ref(&d));
+            (x2, -y2, d1)
+        };
+
+        let v1 = f1.a.clone() / &d1;
+        let v2 = f2.a.clone() / &d1;
+        let (_, r) = (y1 * y2 * &n - x2 * &f2.c).div_rem_floor(v1.clone());
+        let b = v2.clone() * &r * 2u32 + &f2.b;
+        let a = v1 * v2;
+        let c = (b.clone().square() - &self.discriminant) / (a.clone() * 4u32);
+        QuadraticForm { a, b, c }.reduce()
+    }
+
+    /// Map a seed to a group element: the first prime `a ≡ 3 (mod 4)` at or
+    /// above the seed for which `D` is a square mod `a`, with `b = sqrt(D) mod a`
+    pub fn hash_to_form(&self, seed: &Integer) -> QuadraticForm {
+        let mut a = seed.clone() | 3u32;
+        while a.is_probably_prime(30) == IsPrime::No || self.discriminant.jacobi(&a) != 1 {
+            a += 4;
+        }
+
+        // a ≡ 3 (mod 4), so sqrt(D) = D^((a + 1) / 4) mod a
+        let (_, residue) = self.discriminant.clone().div_rem_floor(a.clone());
+        let exponent = (a.clone() + 1u32) >> 2;
+        let mut b = residue.pow_mod(&exponent, &a)
+            .expect("Exponent is non-negative");
+        // b must share the parity of D
+        if b.is_even() {
+            b = a.clone() - b;
+        }
+        let c = (b.clone().square() - &self.discriminant) / (a.clone() * 4u32);
+        QuadraticForm { a, b, c }.reduce()
+    }
+}
+
+impl VdfGroup for ClassGroup {
+    type Element = QuadraticForm;
+
+    /// Big-endian magnitude of the discriminant
+    fn description(&self) -> Vec<u8> {
+        self.discriminant.to_digits::<u8>(Order::Msf)
+    }
+
+    /// Principal form `(1, 1, (1 - D) / 4)`
+    fn identity(&self) -> QuadraticForm {
+        let c = (Integer::from(1) - &self.discriminant) >> 2;
+        QuadraticForm { a: Integer::from(1), b: Integer::from(1), c }.reduce()
+    }
+
+    fn mul(&self, a: &QuadraticForm, b: &QuadraticForm) -> QuadraticForm {
+        self.compose(a, b)
+    }
+
+    fn element_from_seed(&self, seed: &[u8; 32]) -> Result<QuadraticForm, VdfError> {
+        Ok(self.hash_to_form(&Integer::from_digits(seed, Order::Lsf)))
+    }
+
+    fn element_bytes(&self) -> usize {
+        2 * self.coefficient_bytes() + 1
+    }
+
+    fn encode(&self, element: &QuadraticForm) -> Result<Vec<u8>, VdfError> {
+        self.encode_form(element)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<QuadraticForm, VdfError> {
+        self.decode_form(bytes)
+    }
+
+    fn is_valid(&self, element: &QuadraticForm) -> bool {
+        *element != self.identity()
+            && self.form(element.a.clone(), element.b.clone()).is_ok_and(|form| form == *element)
+    }
+}
+
+/// Wesolowski VDF over a class group with a seed-derived discriminant
+pub struct ClassGroupVdf {
+    group: ClassGroup,
+    checkpoint_config: CheckpointConfig,
+}
+
+impl ClassGroupVdf {
+    /// Create a VDF over the class group derived from `seed`
+    pub fn from_seed(seed: &[u8]) -> Self {
+        Self::with_discriminant_bits(seed, DEFAULT_DISCRIMINANT_BITS)
+    }
+
+    /// Create a VDF with a custom discriminant size
+    pub fn with_discriminant_bits(seed: &[u8], bits: u32) -> Self {
+        Self {
+            group: ClassGroup::from_seed(seed, bits),
+            checkpoint_config: CheckpointConfig::default(),
+        }
+    }
+
+    /// Use a custom checkpoint interval and memory budget for proof generation
+    pub fn with_checkpoint_config(mut self, config: CheckpointConfig) -> Self {
+        self.checkpoint_config = config;
+        self
+    }
+
+    pub fn group(&self) -> &ClassGroup {
+        &self.group
+    }
+
+    /// Parameter set recorded in this VDF's outputs
+    pub fn parameters(&self) -> ParameterSet {
+        ParameterSet::class_group(self.group.discriminant().significant_bits(), &self.group.digest())
+    }
+
+    /// Group element the action details map to
+    pub fn generate_input_form(&self, input: &VDFInput) -> QuadraticForm {
+        self.group.hash_to_form(&input_seed(input))
+    }
+
+    /// Compute VDF output by repeated squaring in the class group
+    pub fn compute(&self, input: &VDFInput, iterations: u64) -> Result<VDFOutput, VdfError> {
+        let start_time = SystemTime::now();
+
+        let input_form = self.generate_input_form(input);
+        let plan = CheckpointPlan::new(iterations, &self.checkpoint_config);
+        let mut checkpoints = Vec::with_capacity(plan.checkpoint_count() as usize);
+        let mut result = input_form.clone();
+        for i in 0..iterations {
+            if i.is_multiple_of(plan.interval) {
+                checkpoints.push(result.clone());
+            }
+            result = self.group.square(&result);
+        }
+
+        let computation_time = start_time.elapsed()?.as_secs_f64();
+
+        let parameters = self.parameters();
+        let context = parameters.challenge_context(SchemeKind::Wesolowski, &input.action_type);
+        let proof = self.generate_proof(&plan, &context, &checkpoints, &input_form, &result)?;
+
+        Ok(VDFOutput {
+            input: input.clone(),
+            output: self.group.encode_form(&result)?,
+            iterations,
+            proof: self.group.encode_form(&proof)?,
+            computation_time,
+            scheme: SchemeKind::Wesolowski,
+            parameters,
+        })
+    }
+
+    /// Wesolowski proof `pi = input^floor(2^iterations / l)` from the
+    /// checkpoints, using the same digit bucketing as the RSA prover
+    fn generate_proof(&self, plan: &CheckpointPlan, context: &[u8], checkpoints: &[QuadraticForm], input: &QuadraticForm, output: &QuadraticForm) -> Result<QuadraticForm, VdfError> {
+        let challenge = to_integer(&challenge_in(&self.group, plan.iterations, context, input, output)?);
+        let window = plan.window_bits as u64;
+        let digits = plan.digit_count();
+        let two = Integer::from(2);
+
+        let step = two.clone().pow_mod(&Integer::from(plan.interval), &challenge)
+            .map_err(|e| VdfError::Arithmetic(format!("Modular exponentiation error: {}", e)))?;
+
+        // `None` stands for the identity, which keeps empty buckets free
+        let mut buckets: Vec<Option<QuadraticForm>> = vec![None; 1 << plan.window_bits];
+        let mut proof = self.group.identity();
+        for offset in (0..plan.stride).rev() {
+            for _ in 0..window {
+                proof = self.group.square(&proof);
+            }
+
+            let mut remainder = Integer::new();
+            let mut previous_shift: Option<u64> = None;
+            for (index, checkpoint) in checkpoints.iter().enumerate().rev() {
+                let position = index as u64 * plan.stride + offset;
+                if position >= digits {
+                    continue;
+                }
+
+                let remaining = plan.iterations - position * window;
+                let width = remaining.min(window);
+                let shift = remaining - width;
+                remainder = match previous_shift {
+                    Some(previous) if shift == previous + plan.interval => (remainder * &step) % &challenge,
+                    _ => two.clone().pow_mod(&Integer::from(shift), &challenge)
+                        .map_err(|e| VdfError::Arithmetic(format!("Modular exponentiation error: {}", e)))?,
+                };
+                previous_shift = Some(shift);
+
+                let digit = (remainder.clone() << width as u32) / &challenge;
+                let digit = digit.to_usize()
+                    .ok_or_else(|| VdfError::Arithmetic("Quotient digit out of range".to_string()))?;
+                if digit != 0 {
+                    let merged = match buckets[digit].take() {
+                        Some(bucket) => self.group.compose(&bucket, checkpoint),
+                        None => checkpoint.clone(),
+                    };
+                    buckets[digit] = Some(merged);
+                }
+            }
+
+            let mut suffix: Option<QuadraticForm> = None;
+            let mut partial: Option<QuadraticForm> = None;
+            for bucket in buckets.iter_mut().skip(1).rev() {
+                if let Some(bucket) = bucket.take() {
+                    suffix = Some(match suffix {
+                        Some(suffix) => self.group.compose(&suffix, &bucket),
+                        None => bucket,
+                    });
+                }
+                if let Some(suffix) = &suffix {
+                    partial = Some(match partial {
+                        Some(partial) => self.group.compose(&partial, suffix),
+                        None => suffix.clone(),
+                    });
+                }
+            }
+            if let Some(partial) = partial {
+                proof = self.group.compose(&proof, &partial);
+            }
+        }
+
+        Ok(proof)
+    }
+
+    /// Verify Wesolowski proof: input^r × pi^l = output, with the input form
+    /// and challenge context derived from `input`
+    pub fn verify(&self, input: &VDFInput, output: &QuadraticForm, iterations: u64, proof: &QuadraticForm) -> Result<bool, VdfError> {
+        let context = self.parameters().challenge_context(SchemeKind::Wesolowski, &input.action_type);
+        verify_in_group(&self.group, &context, &self.generate_input_form(input), output, iterations, proof)
+    }
+
+    /// Verify a `VDFOutput` as returned by `compute`
+    pub fn verify_output(&self, output: &VDFOutput) -> Result<bool, VdfError> {
+        self.parameters().check_output(output)?;
+        verify_output_in(&self.group, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+
+    fn test_input() -> VDFInput {
+        VDFInput {
+            player_id: 1,
+            action_type: "build".to_string(),
+            action_id: 4,
+            timestamp: 1234567890,
+            nonce: 1,
+            random_salt: 12345,
+        }
+    }
+
+    #[test]
+    fn test_discriminant_from_seed() {
+        let group = ClassGroup::from_seed(b"season-1", 256);
+        assert_eq!(group, ClassGroup::from_seed(b"season-1", 256));
+        assert_ne!(group, ClassGroup::from_seed(b"season-2", 256));
+
+        let p = Integer::from(-group.discriminant());
+        assert_eq!(p.significant_bits(), 256);
+        assert_eq!(p.mod_u(4), 3);
+        assert_ne!(p.is_probably_prime(30), IsPrime::No);
+    }
+
+    #[test]
+    fn test_group_laws() {
+        let group = ClassGroup::from_seed(b"season-1", 256);
+        let f = group.hash_to_form(&Integer::from(1_000_003u64));
+        let g = group.hash_to_form(&Integer::from(987_654_321u64));
+        let h = group.hash_to_form(&Integer::from(555_555_555u64));
+
+        assert_eq!(group.compose(&f, &group.identity()), f);
+        assert_eq!(group.compose(&f, &g), group.compose(&g, &f));
+        assert_eq!(
+            group.compose(&group.compose(&f, &g), &h),
+            group.compose(&f, &group.compose(&g, &h)),
+        );
+        assert_eq!(
+            group.compose(&group.exp(&f, &BigUint::from(1234u32)), &group.exp(&f, &BigUint::from(4321u32))),
+            group.exp(&f, &BigUint::from(5555u32)),
+        );
+        assert!(group.is_valid(&f));
+        assert!(!group.is_valid(&QuadraticForm { a: f.a.clone(), b: f.b.clone(), c: f.c.clone() + 1u32 }));
+
+        let inverse = QuadraticForm { a: f.a.clone(), b: -f.b.clone(), c: f.c.clone() }.reduce();
+        assert_eq!(group.compose(&f, &inverse), group.identity());
+
+        let encoded = group.encode_form(&inverse).expect("Failed to encode form");
+        assert_eq!(encoded.len(), 2 * 16 + 1);
+        assert_eq!(group.decode_form(&encoded).expect("Failed to decode form"), inverse);
+    }
+
+    #[test]
+    fn test_class_group_vdf_round_trip() {
+        let vdf = ClassGroupVdf::with_discriminant_bits(b"season-1", 256);
+        let result = vdf.compute(&test_input(), 500).expect("VDF computation failed");
+
+        let input = test_input();
+        let output = vdf.group().decode_form(&result.output).expect("Failed to parse output");
+        let proof = vdf.group().decode_form(&result.proof).expect("Failed to parse proof");
+
+        assert!(vdf.verify(&input, &output, 500, &proof).expect("Verification failed"));
+        assert!(!vdf.verify(&input, &output, 499, &proof).expect("Verification failed"));
+
+        let wrong_output = vdf.group().square(&output);
+        assert!(!vdf.verify(&input, &wrong_output, 500, &proof).expect("Verification failed"));
+
+        assert!(vdf.verify_output(&result).expect("Verification failed"));
+        let mut replayed = result.clone();
+        replayed.iterations = 499;
+        assert!(!vdf.verify_output(&replayed).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_short_delays_verify_with_identity_proof() {
+        let vdf = ClassGroupVdf::with_discriminant_bits(b"season-1", 256);
+        for iterations in [0, 1, 2, 10, 100, 200, 255] {
+            let result = vdf.compute(&test_input(), iterations).expect("VDF computation failed");
+            let proof = vdf.group().decode_form(&result.proof).expect("Failed to parse proof");
+            assert_eq!(proof, vdf.group().identity(), "T = {}", iterations);
+            assert!(vdf.verify_output(&result).expect("Verification failed"), "T = {} rejected", iterations);
+        }
+    }
+
+    #[test]
+    fn test_proof_is_rejected_in_another_season() {
+        let season_one = ClassGroupVdf::with_discriminant_bits(b"season-1", 256);
+        let season_two = ClassGroupVdf::with_discriminant_bits(b"season-2", 256);
+        let result = season_one.compute(&test_input(), 200).expect("VDF computation failed");
+
+        assert!(season_two.group().decode_form(&result.output).is_err());
+        assert!(matches!(season_two.verify_output(&result), Err(VdfError::ParameterMismatch { .. })));
+        assert_ne!(season_one.parameters().modulus_id, season_two.parameters().modulus_id);
+
+        // The set name alone identifies the season
+        let decoded = VDFOutput::from_bytes(&result.to_bytes()).expect("Decoding failed");
+        assert_eq!(decoded.parameters, season_one.parameters());
+        assert!(season_one.verify_output(&decoded).expect("Verification failed"));
+    }
+}
@@ -0,0 +1,364 @@
+//! Continuations: one aggregated proof for a chain of sequential actions
+//!
+//! A continuation runs the delays of several actions back to back. The
+//! first segment starts from the usual input seed; every later segment
+//! starts from a seed that hashes the previous segment's output together
+//! with its own action, so segment `k` cannot begin before segment `k - 1`
+//! has finished:
+//!
+//! ```text
+//! seed_0 = seed_digest(action_0)
+//! seed_k = SHA-256(SEED_TAG || u32(len(y_{k-1})) || y_{k-1} || seed_digest(action_k))
+//! x_k    = element_from_seed(seed_k),   y_k = x_k^(2^T_k)
+//! ```
+//!
+//! Instead of one Wesolowski proof per segment, a single element proves all
+//! of them (Wesolowski's aggregation over a shared challenge):
+//!
+//! ```text
+//! d     = SHA-256(CHALLENGE_TAG || u32(n) || for each k:
+//!                 u32(len(ctx_k)) || ctx_k || u64(T_k) || x_k || y_k)
+//! a_k   = first 128 bits of SHA-256(d || u32(k))
+//! l     = hash_to_prime(group, sum T_k, CHALLENGE_TAG || d, x_0, y_{n-1})
+//! pi    = prod x_k^(a_k * floor(2^T_k / l))
+//! check   pi^l * prod x_k^(a_k * (2^T_k mod l)) = prod y_k^(a_k)
+//! ```
+//!
+//! `ctx_k` is the Wesolowski challenge context of action `k` under the
+//! continuation's parameter set, so the digest binds every action, every
+//! segment boundary and the total delay. Like a `VDFOutput`, a continuation
+//! records its `ParameterSet`; continuations saved before sets were recorded
+//! belong to the legacy set.
+
+use num_bigint::BigUint;
+#[cfg(feature = "gmp")]
+use rug::Integer;
+use serde::{Serialize, Deserialize};
+use sha2::{Sha256, Digest};
+
+#[cfg(feature = "gmp")]
+use crate::checkpoint::{CheckpointConfig, CheckpointPlan, SquaringTrace};
+use crate::error::VdfError;
+use crate::group::VdfGroup;
+use crate::hash_to_prime::hash_to_prime;
+use crate::params::ParameterSet;
+#[cfg(feature = "gmp")]
+use crate::rsa_group::{to_integer, RugRsaGroup};
+use crate::scheme::SchemeKind;
+use crate::wesolowski;
+use crate::{seed_digest, VDFInput};
+
+/// Domain separation tag for the seeds of later segments
+pub const SEED_TAG: &[u8] = b"zkgame-vdf-continuation-seed-v1";
+
+/// Domain separation tag for the aggregated challenge
+pub const CHALLENGE_TAG: &[u8] = b"zkgame-vdf-continuation-v1";
+
+/// Size of the aggregation coefficients `a_k`
+const COEFFICIENT_BYTES: usize = 16;
+
+/// One action of a continuation and the delay spent on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segment {
+    pub input: VDFInput,
+    pub iterations: u64,
+    #[serde(with = "crate::encoding::hex_bytes")]
+    pub output: Vec<u8>,
+}
+
+/// Outputs of a chain of actions with one aggregated proof
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinuationOutput {
+    pub segments: Vec<Segment>,
+    #[serde(with = "crate::encoding::hex_bytes")]
+    pub proof: Vec<u8>,
+    pub computation_time: f64,
+    /// Continuations saved before sets were recorded belong to the legacy set
+    #[serde(default = "ParameterSet::legacy")]
+    pub parameters: ParameterSet,
+}
+
+impl ContinuationOutput {
+    /// Sum of the segment delays, or `None` if it overflows
+    pub fn total_iterations(&self) -> Option<u64> {
+        self.segments.iter().try_fold(0u64, |total, segment| total.checked_add(segment.iterations))
+    }
+
+    /// Iteration at which each segment ends, counted from the start of the chain
+    pub fn boundaries(&self) -> Vec<u64> {
+        self.segments.iter()
+            .scan(0u64, |end, segment| {
+                *end = end.saturating_add(segment.iterations);
+                Some(*end)
+            })
+            .collect()
+    }
+}
+
+/// Seed of a segment: the plain input seed for the first segment, bound to
+/// the encoded output of the previous segment for every later one
+pub fn segment_seed(previous_output: Option<&[u8]>, input: &VDFInput) -> [u8; 32] {
+    match previous_output {
+        None => seed_digest(input),
+        Some(previous) => {
+            let mut hasher = Sha256::new();
+            hasher.update(SEED_TAG);
+            hasher.update((previous.len() as u32).to_be_bytes());
+            hasher.update(previous);
+            hasher.update(seed_digest(input));
+            hasher.finalize().into()
+        }
+    }
+}
+
+/// `x_k`, `y_k` and `T_k` of one segment
+struct Statement<G: VdfGroup> {
+    context: Vec<u8>,
+    iterations: u64,
+    input: G::Element,
+    output: G::Element,
+}
+
+/// Check the aggregated proof of `output` in `group`, with the challenge
+/// contexts of the parameter set recorded in `output`
+///
+/// Enforces no iteration limit; callers compare `total_iterations` with
+/// their own bound.
+pub fn verify_in<G: VdfGroup>(group: &G, output: &ContinuationOutput) -> Result<bool, VdfError> {
+    if output.segments.is_empty() {
+        return Err(VdfError::MalformedProof("Continuation has no segments".to_string()));
+    }
+    let proof = group.decode(&output.proof)?;
+    let mut statements = Vec::with_capacity(output.segments.len());
+    let mut previous: Option<Vec<u8>> = None;
+    for segment in &output.segments {
+        let seed = segment_seed(previous.as_deref(), &segment.input);
+        let value = group.decode(&segment.output)?;
+        if !group.is_valid(&value) {
+            return Ok(false);
+        }
+        previous = Some(group.encode(&value)?);
+        statements.push(Statement::<G> {
+            context: output.parameters.challenge_context(SchemeKind::Wesolowski, &segment.input.action_type),
+            iterations: segment.iterations,
+            input: group.element_from_seed(&seed)?,
+            output: value,
+        });
+    }
+    if !wesolowski::is_valid_proof(group, &proof) {
+        return Ok(false);
+    }
+
+    let (challenge, coefficients) = challenge(group, &statements)?;
+    let mut left_side = group.exp(&proof, &challenge);
+    let mut right_side = group.identity();
+    for (statement, coefficient) in statements.iter().zip(&coefficients) {
+        let remainder = BigUint::from(2u32).modpow(&BigUint::from(statement.iterations), &challenge);
+        left_side = group.mul(&left_side, &group.exp(&statement.input, &(remainder * coefficient)));
+        right_side = group.mul(&right_side, &group.exp(&statement.output, coefficient));
+    }
+    Ok(left_side == right_side)
+}
+
+/// Challenge prime `l` and coefficients `a_k` for the statements of a chain
+fn challenge<G: VdfGroup>(group: &G, statements: &[Statement<G>]) -> Result<(BigUint, Vec<BigUint>), VdfError> {
+    let mut hasher = Sha256::new();
+    hasher.update(CHALLENGE_TAG);
+    hasher.update((statements.len() as u32).to_be_bytes());
+    let mut total_iterations = 0u64;
+    for statement in statements {
+        hasher.update((statement.context.len() as u32).to_be_bytes());
+        hasher.update(&statement.context);
+        hasher.update(statement.iterations.to_be_bytes());
+        hasher.update(group.encode(&statement.input)?);
+        hasher.update(group.encode(&statement.output)?);
+        total_iterations = total_iterations.checked_add(statement.iterations)
+            .ok_or_else(|| VdfError::MalformedProof("Total iterations overflow".to_string()))?;
+    }
+    let digest = hasher.finalize();
+
+    let coefficients = (0..statements.len() as u32)
+        .map(|index| {
+            let mut hasher = Sha256::new();
+            hasher.update(digest);
+            hasher.update(index.to_be_bytes());
+            BigUint::from_bytes_be(&hasher.finalize()[..COEFFICIENT_BYTES])
+        })
+        .collect();
+
+    let mut context = CHALLENGE_TAG.to_vec();
+    context.extend_from_slice(&digest);
+    let first = statements.first().ok_or_else(|| VdfError::MalformedProof("Continuation has no segments".to_string()))?;
+    let last = &statements[statements.len() - 1];
+    let challenge = hash_to_prime(
+        &group.description(),
+        total_iterations,
+        &context,
+        &group.encode(&first.input)?,
+        &group.encode(&last.output)?,
+    )?;
+    Ok((challenge, coefficients))
+}
+
+/// Square through every action in turn in the group of `parameters` and
+/// aggregate the segment proofs
+///
+/// Each segment keeps its own checkpoint trace laid out by `config`, so
+/// proving costs the same as proving the segments one by one; only one
+/// element is produced.
+#[cfg(feature = "gmp")]
+pub(crate) fn compute(group: &RugRsaGroup, parameters: &ParameterSet, config: &CheckpointConfig, actions: &[(VDFInput, u64)]) -> Result<ContinuationOutput, VdfError> {
+    if actions.is_empty() {
+        return Err(VdfError::MalformedProof("Continuation has no segments".to_string()));
+    }
+    let modulus = group.modulus();
+    let start_time = std::time::SystemTime::now();
+
+    let mut statements = Vec::with_capacity(actions.len());
+    let mut traces = Vec::with_capacity(actions.len());
+    let mut segments = Vec::with_capacity(actions.len());
+    let mut previous: Option<Vec<u8>> = None;
+    for (input, iterations) in actions {
+        let seed = segment_seed(previous.as_deref(), input);
+        let start = group.element_from_seed(&seed)?;
+        let mut trace = SquaringTrace::new(CheckpointPlan::new(*iterations, config));
+        let mut current = start.clone();
+        for i in 0..*iterations {
+            trace.observe(i, &current);
+            current = current.square() % modulus;
+        }
+
+        let current = group.canonical(&current);
+        let encoded = group.encode(&current)?;
+        segments.push(Segment {
+            input: input.clone(),
+            iterations: *iterations,
+            output: encoded.clone(),
+        });
+        previous = Some(encoded);
+        traces.push(trace);
+        statements.push(Statement::<RugRsaGroup> {
+            context: parameters.challenge_context(SchemeKind::Wesolowski, &input.action_type),
+            iterations: *iterations,
+            input: start,
+            output: current,
+        });
+    }
+    let computation_time = start_time.elapsed()?.as_secs_f64();
+
+    let (challenge, coefficients) = challenge(group, &statements)?;
+    let challenge = to_integer(&challenge);
+    let mut proof = Integer::from(1);
+    for (trace, coefficient) in traces.iter().zip(&coefficients) {
+        let partial = wesolowski::accumulate(modulus, &challenge, trace.plan(), trace.checkpoints(), 0)?;
+        let partial = partial.pow_mod(&to_integer(coefficient), modulus)
+            .map_err(|e| VdfError::Arithmetic(format!("Modular exponentiation error: {}", e)))?;
+        proof = (proof * partial) % modulus;
+    }
+
+    Ok(ContinuationOutput {
+        segments,
+        proof: group.encode(&group.canonical(&proof))?,
+        computation_time,
+        parameters: parameters.clone(),
+    })
+}
+
+#[cfg(all(test, feature = "gmp"))]
+mod tests {
+    use super::*;
+    use crate::{BigUintRsaGroup, VDFEngine};
+
+    fn action(action_type: &str, action_id: u64) -> VDFInput {
+        VDFInput {
+            player_id: 8,
+            action_type: action_type.to_string(),
+            action_id,
+            timestamp: 1234567890,
+            nonce: 1,
+            random_salt: 4242,
+        }
+    }
+
+    fn chain() -> Vec<(VDFInput, u64)> {
+        vec![(action("gather", 1), 300), (action("craft", 2), 500), (action("build", 3), 257)]
+    }
+
+    #[test]
+    fn test_continuation_round_trip() {
+        let engine = VDFEngine::new();
+        let output = engine.compute_continuation(&chain()).expect("Continuation failed");
+        assert_eq!(output.segments.len(), 3);
+        assert_eq!(output.total_iterations(), Some(1057));
+        assert_eq!(output.boundaries(), vec![300, 800, 1057]);
+        assert!(engine.verify_continuation(&output).expect("Verification failed"));
+        assert!(verify_in(&BigUintRsaGroup::rsa_2048(), &output).expect("Verification failed"));
+
+        let json = serde_json::to_string(&output).expect("Serialization failed");
+        let decoded: ContinuationOutput = serde_json::from_str(&json).expect("Deserialization failed");
+        assert_eq!(decoded.parameters, ParameterSet::current());
+        assert!(engine.verify_continuation(&decoded).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_verifies_under_the_recorded_set() {
+        let engine = VDFEngine::new();
+        let output = engine.compute_continuation(&chain()).expect("Continuation failed");
+
+        // Saved before sets were recorded: read as legacy, which the current engine refuses
+        let mut json = serde_json::to_value(&output).expect("Serialization failed");
+        json.as_object_mut().expect("Continuation is an object").remove("parameters");
+        let saved: ContinuationOutput = serde_json::from_value(json).expect("Deserialization failed");
+        assert_eq!(saved.parameters, ParameterSet::legacy());
+        assert!(matches!(engine.verify_continuation(&saved), Err(VdfError::ParameterMismatch { .. })));
+
+        // The legacy engine checks it under the legacy contexts, where the proof does not hold
+        let legacy = VDFEngine::from_parameters(&ParameterSet::legacy()).expect("Published set");
+        assert!(!legacy.verify_continuation(&saved).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_segments_follow_the_previous_output() {
+        let engine = VDFEngine::new();
+        let output = engine.compute_continuation(&chain()).expect("Continuation failed");
+
+        // The first segment is an ordinary VDF for its action
+        let first = engine.compute(&chain()[0].0, 300).expect("VDF computation failed");
+        assert_eq!(output.segments[0].output, first.output);
+
+        // Later segments start from the previous output, not the plain seed
+        let second = engine.compute(&chain()[1].0, 500).expect("VDF computation failed");
+        assert_ne!(output.segments[1].output, second.output);
+    }
+
+    #[test]
+    fn test_rejects_altered_chains() {
+        let engine = VDFEngine::new();
+        let output = engine.compute_continuation(&chain()).expect("Continuation failed");
+
+        let mut moved_boundary = output.clone();
+        moved_boundary.segments[0].iterations += 1;
+        moved_boundary.segments[1].iterations -= 1;
+        assert!(!engine.verify_continuation(&moved_boundary).expect("Verification failed"));
+
+        let mut reordered = output.clone();
+        reordered.segments.swap(1, 2);
+        assert!(!engine.verify_continuation(&reordered).expect("Verification failed"));
+
+        let mut truncated = output.clone();
+        truncated.segments.pop();
+        assert!(!engine.verify_continuation(&truncated).expect("Verification failed"));
+
+        let mut other_action = output.clone();
+        other_action.segments[2].input.action_type = "travel".to_string();
+        assert!(!engine.verify_continuation(&other_action).expect("Verification failed"));
+
+        let mut zero = output.clone();
+        zero.proof = vec![0; crate::ELEMENT_BYTES];
+        assert!(!engine.verify_continuation(&zero).expect("Verification failed"));
+
+        let mut empty = output;
+        empty.segments.clear();
+        assert!(engine.verify_continuation(&empty).is_err());
+    }
+}
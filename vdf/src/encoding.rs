@@ -0,0 +1,255 @@
+//! Canonical byte encodings for group elements and VDF outputs
+//!
+//! RSA group elements are written as 256-byte big-endian integers, the
+//! operand layout of the EVM modexp precompile (EIP-198), so `output` and
+//! `proof` bytes can be forwarded to `VDFVerifier.sol` as calldata without
+//! re-encoding. A Pietrzak proof is the concatenation of its elements.
+//!
+//! The binary `VDFOutput` layout is defined in `zkgame_vdf_core::statement`,
+//! so the WebAssembly verifier reads exactly what this crate writes.
+//!
+//! In JSON, byte fields are written as `0x`-prefixed hex by default; the
+//! `base64_bytes` helper is available for more compact transports.
+
+use num_bigint::BigUint;
+#[cfg(feature = "gmp")]
+use rug::Integer;
+#[cfg(feature = "gmp")]
+use rug::integer::Order;
+use serde::{Deserialize, Deserializer, Serializer};
+use zkgame_vdf_core::Statement;
+
+use crate::error::VdfError;
+use crate::params::ParameterSet;
+use crate::VDFOutput;
+
+pub use zkgame_vdf_core::ELEMENT_BYTES;
+
+/// Left-pad big-endian `digits` to the fixed element width
+pub(crate) fn pad_element(digits: &[u8]) -> Result<[u8; ELEMENT_BYTES], VdfError> {
+    Ok(zkgame_vdf_core::encoding::pad_element(digits)?)
+}
+
+/// Fixed-width big-endian encoding of a group element
+#[cfg(feature = "gmp")]
+pub fn element_to_bytes(value: &Integer) -> Result<[u8; ELEMENT_BYTES], VdfError> {
+    if *value < 0 {
+        return Err(VdfError::MalformedProof("Group element is negative".to_string()));
+    }
+    pad_element(&value.to_digits::<u8>(Order::Msf))
+}
+
+/// Decode a group element written by `element_to_bytes`
+#[cfg(feature = "gmp")]
+pub fn element_from_bytes(bytes: &[u8]) -> Result<Integer, VdfError> {
+    if bytes.len() != ELEMENT_BYTES {
+        return Err(VdfError::MalformedProof(format!(
+            "Group element must be {} bytes, got {}",
+            ELEMENT_BYTES,
+            bytes.len()
+        )));
+    }
+    Ok(Integer::from_digits(bytes, Order::Msf))
+}
+
+/// Concatenate the fixed-width encodings of `elements`
+#[cfg(feature = "gmp")]
+pub fn encode_elements(elements: &[Integer]) -> Result<Vec<u8>, VdfError> {
+    let mut bytes = Vec::with_capacity(elements.len() * ELEMENT_BYTES);
+    for element in elements {
+        bytes.extend_from_slice(&element_to_bytes(element)?);
+    }
+    Ok(bytes)
+}
+
+/// Split concatenated fixed-width elements
+#[cfg(feature = "gmp")]
+pub fn decode_elements(bytes: &[u8]) -> Result<Vec<Integer>, VdfError> {
+    if !bytes.len().is_multiple_of(ELEMENT_BYTES) {
+        return Err(VdfError::MalformedProof(format!(
+            "Proof length {} is not a multiple of {} bytes",
+            bytes.len(),
+            ELEMENT_BYTES
+        )));
+    }
+    bytes.chunks(ELEMENT_BYTES).map(element_from_bytes).collect()
+}
+
+impl VDFOutput {
+    /// Output as an RSA group element
+    #[cfg(feature = "gmp")]
+    pub fn output_element(&self) -> Result<Integer, VdfError> {
+        element_from_bytes(&self.output)
+    }
+
+    /// Proof as RSA group elements (one for Wesolowski, one per round for Pietrzak)
+    #[cfg(feature = "gmp")]
+    pub fn proof_elements(&self) -> Result<Vec<Integer>, VdfError> {
+        decode_elements(&self.proof)
+    }
+
+    /// Canonical binary encoding, see `zkgame_vdf_core::Statement::to_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Statement {
+            scheme: self.scheme.into(),
+            parameters: self.parameters.name.clone(),
+            input: self.input.clone(),
+            iterations: self.iterations,
+            computation_time: self.computation_time,
+            output: self.output.clone(),
+            proof: self.proof.clone(),
+        }.to_bytes()
+    }
+
+    /// Decode the binary encoding written by `to_bytes`, or by format version 1
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VdfError> {
+        let statement = Statement::from_bytes(bytes)?;
+        Ok(VDFOutput {
+            parameters: ParameterSet::from_name(&statement.parameters)?,
+            input: statement.input,
+            output: statement.output,
+            iterations: statement.iterations,
+            proof: statement.proof,
+            computation_time: statement.computation_time,
+            scheme: statement.scheme.into(),
+        })
+    }
+}
+
+/// Serde helper writing bytes as `0x`-prefixed hex
+///
+/// Strings without the prefix are read as the comma-separated decimal
+/// element lists written before the byte encoding existed.
+pub mod hex_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        decode(&encoded).map_err(serde::de::Error::custom)
+    }
+
+    fn decode(encoded: &str) -> Result<Vec<u8>, VdfError> {
+        if let Some(digits) = encoded.strip_prefix("0x") {
+            return hex::decode(digits)
+                .map_err(|e| VdfError::MalformedProof(format!("Invalid hex: {}", e)));
+        }
+
+        // Legacy decimal encoding
+        if encoded.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut bytes = Vec::new();
+        for element in encoded.split(',') {
+            let value = BigUint::parse_bytes(element.as_bytes(), 10)
+                .ok_or_else(|| VdfError::MalformedProof(format!("Invalid decimal element: {}", element)))?;
+            bytes.extend_from_slice(&pad_element(&value.to_bytes_be())?);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Serde helper writing bytes as standard base64
+pub mod base64_bytes {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "gmp"))]
+mod tests {
+    use super::*;
+    use crate::{SchemeKind, VDFInput};
+
+    fn sample_output() -> VDFOutput {
+        VDFOutput {
+            input: VDFInput {
+                player_id: 42,
+                action_type: "craft".to_string(),
+                action_id: 7,
+                timestamp: 1234567890,
+                nonce: 3,
+                random_salt: 99,
+            },
+            output: encode_elements(&[Integer::from(123_456_789u64)]).expect("Encoding failed"),
+            iterations: 1000,
+            proof: encode_elements(&[Integer::from(987_654_321u64)]).expect("Encoding failed"),
+            computation_time: 0.25,
+            scheme: SchemeKind::Wesolowski,
+            parameters: ParameterSet::current(),
+        }
+    }
+
+    #[test]
+    fn test_element_round_trip() {
+        let value = (Integer::from(1) << 2047u32) + 12345u32;
+        let bytes = element_to_bytes(&value).expect("Encoding failed");
+        assert_eq!(bytes[0], 0x80);
+        assert_eq!(element_from_bytes(&bytes).expect("Decoding failed"), value);
+
+        assert!(element_to_bytes(&(Integer::from(1) << 2048u32)).is_err());
+        assert!(element_to_bytes(&Integer::from(-1)).is_err());
+        assert!(element_from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_output_binary_round_trip() {
+        let output = sample_output();
+        let decoded = VDFOutput::from_bytes(&output.to_bytes()).expect("Decoding failed");
+        assert_eq!(decoded.to_bytes(), output.to_bytes());
+        assert_eq!(decoded.output_element().expect("Bad output"), Integer::from(123_456_789u64));
+
+        let mut truncated = output.to_bytes();
+        truncated.pop();
+        assert!(VDFOutput::from_bytes(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_format_version_1_decodes_as_legacy() {
+        let output = sample_output();
+        let name_field = 4 + output.parameters.name.len();
+        let mut version_1 = output.to_bytes();
+        version_1.drain(2..2 + name_field);
+        version_1[0] = 1;
+        let decoded = VDFOutput::from_bytes(&version_1).expect("Decoding failed");
+        assert_eq!(decoded.parameters, ParameterSet::legacy());
+        assert_eq!(decoded.input, output.input);
+        assert_eq!(decoded.proof, output.proof);
+
+        let mut unknown = sample_output();
+        unknown.parameters.name = "rsa4096-v9".to_string();
+        assert!(matches!(VDFOutput::from_bytes(&unknown.to_bytes()), Err(VdfError::UnsupportedParameters(_))));
+    }
+
+    #[test]
+    fn test_json_uses_hex_and_reads_legacy_decimal() {
+        let output = sample_output();
+        let json = serde_json::to_value(&output).expect("Serialization failed");
+        let hex_output = json["output"].as_str().expect("Output is not a string");
+        assert!(hex_output.starts_with("0x"));
+        assert_eq!(hex_output.len(), 2 + 2 * ELEMENT_BYTES);
+
+        let mut legacy = json.clone();
+        legacy["output"] = serde_json::Value::String("123456789".to_string());
+        let decoded: VDFOutput = serde_json::from_value(legacy).expect("Legacy decoding failed");
+        assert_eq!(decoded.output, output.output);
+
+        // Outputs saved before parameter sets were recorded
+        let mut unversioned = json.clone();
+        unversioned.as_object_mut().expect("Output is an object").remove("parameters");
+        let decoded: VDFOutput = serde_json::from_value(unversioned).expect("Legacy decoding failed");
+        assert_eq!(decoded.parameters, ParameterSet::legacy());
+    }
+}
@@ -0,0 +1,78 @@
+//! Error type shared by all VDF backends
+
+use std::fmt;
+
+use zkgame_vdf_core::DecodeError;
+
+/// Errors returned by VDF computation and verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfError {
+    /// The input seed does not map to a valid group element
+    InvalidSeed,
+    /// A proof, output or group element could not be decoded
+    MalformedProof(String),
+    /// The requested iteration count is above the engine's `max_iterations`
+    TooManyIterations { iterations: u64, max_iterations: u64 },
+    /// The computation was cancelled before it finished
+    Cancelled,
+    /// The system clock failed while timing a computation
+    Timing(String),
+    /// Big-integer arithmetic failed on otherwise valid data
+    Arithmetic(String),
+    /// A saved computation snapshot is corrupt or does not match the computation
+    InvalidSnapshot(String),
+    /// Reading or writing a snapshot or calibration file failed
+    Io(String),
+    /// A calibration profile is not valid JSON or has an unsupported version
+    InvalidCalibration(String),
+    /// An output was made with another parameter set than the verifier's
+    ParameterMismatch { expected: String, found: String },
+    /// A parameter set is unknown or cannot be used for this operation
+    UnsupportedParameters(String),
+}
+
+impl fmt::Display for VdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VdfError::InvalidSeed => write!(f, "Input seed does not map to a valid group element"),
+            VdfError::MalformedProof(reason) => write!(f, "Malformed proof: {}", reason),
+            VdfError::TooManyIterations { iterations, max_iterations } => write!(
+                f,
+                "Iteration count {} exceeds the maximum of {}",
+                iterations, max_iterations
+            ),
+            VdfError::Cancelled => write!(f, "VDF computation was cancelled"),
+            VdfError::Timing(reason) => write!(f, "Time measurement error: {}", reason),
+            VdfError::Arithmetic(reason) => write!(f, "Arithmetic error: {}", reason),
+            VdfError::InvalidSnapshot(reason) => write!(f, "Invalid snapshot: {}", reason),
+            VdfError::Io(reason) => write!(f, "I/O error: {}", reason),
+            VdfError::InvalidCalibration(reason) => write!(f, "Invalid calibration profile: {}", reason),
+            VdfError::ParameterMismatch { expected, found } => write!(
+                f,
+                "Output uses parameter set {}, expected {}",
+                found, expected
+            ),
+            VdfError::UnsupportedParameters(reason) => write!(f, "Unsupported parameters: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for VdfError {}
+
+impl From<std::time::SystemTimeError> for VdfError {
+    fn from(error: std::time::SystemTimeError) -> Self {
+        VdfError::Timing(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for VdfError {
+    fn from(error: std::io::Error) -> Self {
+        VdfError::Io(error.to_string())
+    }
+}
+
+impl From<DecodeError> for VdfError {
+    fn from(error: DecodeError) -> Self {
+        VdfError::MalformedProof(error.to_string())
+    }
+}
@@ -0,0 +1,113 @@
+//! Group backends for the squaring chain
+//!
+//! Verification only needs a handful of operations on the group the chain
+//! lives in. `VdfGroup` collects them so the Wesolowski and Pietrzak
+//! verifiers are written once for every backend:
+//!
+//! - `RugRsaGroup`, RSA-2048 on GMP, used by `VDFEngine` (feature `gmp`)
+//! - `BigUintRsaGroup`, RSA-2048 in pure Rust, for verifier-only builds
+//! - `ClassGroup`, a seed-derived class group (feature `gmp`)
+//!
+//! The `gmp` feature is on by default. Consumers that only check proofs can
+//! depend on this crate with `default-features = false` and verify with
+//! `ParameterSet::current().verify_output(&output)`, which checks the
+//! output's parameter set and uses `BigUintRsaGroup`.
+
+use std::fmt;
+
+use num_bigint::BigUint;
+
+use crate::error::VdfError;
+use crate::scheme::SchemeKind;
+use crate::{pietrzak, seed_digest, wesolowski, VDFOutput};
+
+/// A finite abelian group of unknown order with a canonical byte encoding
+pub trait VdfGroup {
+    type Element: Clone + PartialEq + fmt::Debug;
+
+    /// Bytes identifying the group, hashed into every challenge
+    fn description(&self) -> Vec<u8>;
+
+    fn identity(&self) -> Self::Element;
+
+    /// Group operation, returning a canonical representative
+    fn mul(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+
+    fn square(&self, element: &Self::Element) -> Self::Element {
+        self.mul(element, element)
+    }
+
+    /// `base^exponent` by left-to-right square-and-multiply
+    fn exp(&self, base: &Self::Element, exponent: &BigUint) -> Self::Element {
+        let mut result = self.identity();
+        for bit in (0..exponent.bits()).rev() {
+            result = self.square(&result);
+            if exponent.bit(bit) {
+                result = self.mul(&result, base);
+            }
+        }
+        result
+    }
+
+    /// Element the 256-bit input seed of an action maps to
+    fn element_from_seed(&self, seed: &[u8; 32]) -> Result<Self::Element, VdfError>;
+
+    /// Size of one encoded element
+    fn element_bytes(&self) -> usize;
+
+    /// Fixed-width encoding of `element`
+    fn encode(&self, element: &Self::Element) -> Result<Vec<u8>, VdfError>;
+
+    /// Parse an element written by `encode`
+    ///
+    /// Wrong lengths are always an error. Whether the value is a canonical
+    /// group element is answered by `is_valid`, which the verifiers check.
+    fn decode(&self, bytes: &[u8]) -> Result<Self::Element, VdfError>;
+
+    /// Whether `element` is the canonical representative of a group element
+    /// other than the identity
+    ///
+    /// Outputs and proofs must pass this check. The identity (and anything
+    /// of small order folded into it, such as `-1` in the RSA groups) would
+    /// let an equation hold without the squarings having been done. The one
+    /// exception is a Wesolowski proof, which is the identity for short
+    /// delays, see `wesolowski::is_valid_proof`.
+    fn is_valid(&self, element: &Self::Element) -> bool;
+}
+
+/// Verify a `VDFOutput` against `group` with the scheme recorded in it
+///
+/// This is the backend-independent part of `VDFEngine::verify_output`; it
+/// does not enforce an iteration limit. The challenge uses the statement
+/// version of `output.parameters`, but `group` is taken as given: callers
+/// that accept outputs of several sets pick the group from the set, see
+/// `ParameterSet::verify_output`.
+pub fn verify_output_in<G: VdfGroup>(group: &G, output: &VDFOutput) -> Result<bool, VdfError> {
+    let input = group.element_from_seed(&seed_digest(&output.input))?;
+    let value = group.decode(&output.output)?;
+    let width = group.element_bytes();
+    if !output.proof.len().is_multiple_of(width) {
+        return Err(VdfError::MalformedProof(format!(
+            "Proof length {} is not a multiple of {} bytes",
+            output.proof.len(),
+            width
+        )));
+    }
+    let proof = output.proof.chunks(width)
+        .map(|chunk| group.decode(chunk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let context = output.parameters.challenge_context(output.scheme, &output.input.action_type);
+    verify_statement_in(group, output.scheme, &context, &input, &value, output.iterations, &proof)
+}
+
+/// Check a decoded statement with the verifier of `scheme`
+pub(crate) fn verify_statement_in<G: VdfGroup>(group: &G, scheme: SchemeKind, context: &[u8], input: &G::Element, output: &G::Element, iterations: u64, proof: &[G::Element]) -> Result<bool, VdfError> {
+    match scheme {
+        SchemeKind::Wesolowski => match proof {
+            [proof] => wesolowski::verify_in_group(group, context, input, output, iterations, proof),
+            _ => Ok(false),
+        },
+        SchemeKind::Pietrzak => pietrzak::verify_in_group(group, context, input, output, iterations, proof),
+    }
+}
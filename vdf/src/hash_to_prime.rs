@@ -0,0 +1,71 @@
+//! Versioned hash-to-prime for Wesolowski challenges
+//!
+//! The derivation is specified in `zkgame_vdf_core::hash_to_prime`, which
+//! every verifier shares; this module maps its failure to `VdfError`.
+
+use num_bigint::BigUint;
+
+use crate::error::VdfError;
+
+pub use zkgame_vdf_core::hash_to_prime::{
+    is_prime_fixed_bases, CHALLENGE_BITS, HASH_TO_PRIME_TAG, HASH_TO_PRIME_VERSION, MAX_ATTEMPTS, MILLER_RABIN_BASES,
+};
+
+/// Hash a statement to its challenge prime
+pub fn hash_to_prime(group: &[u8], iterations: u64, context: &[u8], input: &[u8], output: &[u8]) -> Result<BigUint, VdfError> {
+    hash_to_prime_with_counter(group, iterations, context, input, output).map(|(_, prime)| prime)
+}
+
+/// Challenge prime together with the counter of the candidate that was accepted
+pub fn hash_to_prime_with_counter(group: &[u8], iterations: u64, context: &[u8], input: &[u8], output: &[u8]) -> Result<(u32, BigUint), VdfError> {
+    zkgame_vdf_core::hash_to_prime::hash_to_prime_with_counter(group, iterations, context, input, output)
+        .ok_or_else(|| VdfError::Arithmetic("No challenge prime found".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Vectors {
+        version: u8,
+        tag: String,
+        miller_rabin_bases: Vec<u32>,
+        vectors: Vec<Vector>,
+    }
+
+    #[derive(Deserialize)]
+    struct Vector {
+        description: String,
+        group: String,
+        iterations: u64,
+        context: String,
+        input: String,
+        output: String,
+        counter: u32,
+        prime: String,
+    }
+
+    #[test]
+    fn test_published_vectors() {
+        let vectors: Vectors = serde_json::from_str(include_str!("../test_vectors/hash_to_prime_v2.json"))
+            .expect("Invalid test vector file");
+        assert_eq!(vectors.version, HASH_TO_PRIME_VERSION);
+        assert_eq!(vectors.tag.as_bytes(), HASH_TO_PRIME_TAG);
+        assert_eq!(vectors.miller_rabin_bases, MILLER_RABIN_BASES);
+
+        for vector in vectors.vectors {
+            let decode = |field: &str| hex::decode(field).expect("Invalid hex in test vector");
+            let (counter, prime) = hash_to_prime_with_counter(
+                &decode(&vector.group),
+                vector.iterations,
+                &decode(&vector.context),
+                &decode(&vector.input),
+                &decode(&vector.output),
+            ).expect("Hash to prime failed");
+            assert_eq!(counter, vector.counter, "{}", vector.description);
+            assert_eq!(prime, BigUint::parse_bytes(vector.prime.as_bytes(), 16).expect("Invalid prime"), "{}", vector.description);
+        }
+    }
+}
@@ -1,204 +1,638 @@
-//! Verifiable Delay Function (VDF) implementation using Wesolowski's scheme
+//! Verifiable Delay Function (VDF) implementation using Wesolowski's or Pietrzak's scheme
 //! 
 //! This module implements a VDF based on repeated squaring modulo RSA-2048,
 //! providing computational proof of time passage for time-locked game actions.
+//!
+//! Computation and the class group backend need GMP through the default
+//! `gmp` feature. Without it the crate still verifies outputs, see `group`.
 
-use rug::{Integer, ops::Pow};
-use sha2::{Sha256, Digest};
+#[cfg(feature = "gmp")]
+use rug::Integer;
 use serde::{Serialize, Deserialize};
+#[cfg(feature = "gmp")]
+use std::path::Path;
+#[cfg(feature = "gmp")]
 use std::time::{SystemTime, UNIX_EPOCH};
 
-/// RSA-2048 modulus (publicly known, no factorization exists)
-/// This is the standard RSA-2048 modulus used for VDF computations
-const RSA_2048_MODULUS: &str = "25195908475657893494027183240048398571429282126204032027777137836043662020707595556264018525880784406918290641249515082189298559149176184502808489120072844992687392807287776735971418347270261896375014971824691165077613379859095700097330459748808428401797429100642458691817195118746121515172654632282216869987549182422433637259085141865462043576798423387184774447920739934236584823824281198163815010674810451660377306056201619676256133844143603833904414952634432190114657544454178424020924616515723350778707749817125772467962926386356373289912154831438167899885040445364023527381951378636564391212010397122822120720357";
+pub mod beacon;
+#[cfg(feature = "gmp")]
+pub mod calibration;
+#[cfg(feature = "gmp")]
+pub mod checkpoint;
+#[cfg(feature = "gmp")]
+pub mod class_group;
+pub mod continuation;
+pub mod encoding;
+pub mod error;
+pub mod group;
+pub mod hash_to_prime;
+pub mod params;
+pub mod pietrzak;
+pub mod policy;
+#[cfg(feature = "gmp")]
+pub mod progress;
+pub mod rsa_group;
+pub mod scheme;
+#[cfg(feature = "gmp")]
+pub mod snapshot;
+pub mod solidity;
+pub mod wesolowski;
 
-/// Target iterations per second for time calibration
-const ITERATIONS_PER_SECOND: u64 = 278;
+pub use beacon::{Beacon, EpochSeed};
+#[cfg(feature = "gmp")]
+pub use calibration::{CalibrationProfile, TimingMismatch};
+#[cfg(feature = "gmp")]
+pub use checkpoint::{CheckpointConfig, CheckpointPlan, SquaringTrace};
+#[cfg(feature = "gmp")]
+pub use class_group::{ClassGroup, ClassGroupVdf, QuadraticForm};
+pub use continuation::{ContinuationOutput, Segment};
+#[cfg(feature = "gmp")]
+pub use encoding::{decode_elements, element_from_bytes, element_to_bytes, encode_elements};
+pub use encoding::ELEMENT_BYTES;
+pub use error::VdfError;
+pub use group::{verify_output_in, VdfGroup};
+pub use hash_to_prime::{hash_to_prime, HASH_TO_PRIME_VERSION};
+pub use params::ParameterSet;
+pub use policy::{Verdict, VerificationPolicy};
+#[cfg(feature = "gmp")]
+pub use progress::{CancellationToken, ComputeStatus, PartialComputation, Progress, PROGRESS_STEP};
+pub use rsa_group::BigUintRsaGroup;
+#[cfg(feature = "gmp")]
+pub use rsa_group::RugRsaGroup;
+#[cfg(feature = "gmp")]
+pub use scheme::VdfScheme;
+pub use scheme::{challenge_context, SchemeKind, PROOF_VERSION};
 
-/// VDF input seed for binding to specific actions
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VDFInput {
-    pub player_id: u64,
-    pub action_type: String,
-    pub action_id: u64,
-    pub timestamp: u64,
-    pub nonce: u64,
-    pub random_salt: u64,
-}
+pub use zkgame_vdf_core::params::PROTOCOL_ITERATIONS_PER_SECOND;
+pub use zkgame_vdf_core::{VDFInput, DEFAULT_MAX_ITERATIONS};
+
+/// Default squarings between two snapshots in `VDFEngine::compute_persistent`
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 1_000_000;
 
 /// VDF output and proof
+///
+/// `output` and `proof` hold fixed-width big-endian group elements, see `encoding`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VDFOutput {
     pub input: VDFInput,
-    pub output: String,
+    #[serde(with = "encoding::hex_bytes")]
+    pub output: Vec<u8>,
     pub iterations: u64,
-    pub proof: String,
+    #[serde(with = "encoding::hex_bytes")]
+    pub proof: Vec<u8>,
     pub computation_time: f64,
+    #[serde(default)]
+    pub scheme: SchemeKind,
+    /// Outputs saved before sets were recorded belong to the legacy set
+    #[serde(default = "ParameterSet::legacy")]
+    pub parameters: ParameterSet,
 }
 
 /// VDF computation engine
+#[cfg(feature = "gmp")]
 pub struct VDFEngine {
-    modulus: Integer,
+    parameters: ParameterSet,
+    group: RugRsaGroup,
+    checkpoint_config: CheckpointConfig,
+    scheme: SchemeKind,
+    max_iterations: u64,
+    snapshot_interval: u64,
+    calibration: Option<CalibrationProfile>,
+    prover_threads: usize,
 }
 
+#[cfg(feature = "gmp")]
 impl VDFEngine {
-    /// Create a new VDF engine with RSA-2048 modulus
+    /// Create a new VDF engine with the current parameter set
     pub fn new() -> Self {
-        let modulus = Integer::from_str_radix(RSA_2048_MODULUS, 10)
-            .expect("Failed to parse RSA-2048 modulus");
-        
-        Self { modulus }
+        Self {
+            parameters: ParameterSet::current(),
+            group: RugRsaGroup::rsa_2048(),
+            checkpoint_config: CheckpointConfig::default(),
+            scheme: SchemeKind::default(),
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            calibration: None,
+            prover_threads: 1,
+        }
+    }
+
+    /// Engine for a published parameter set
+    ///
+    /// The engine only verifies outputs made with `parameters`. Sets other
+    /// than `ParameterSet::current` are kept so that saved proofs stay
+    /// verifiable; computing with them returns `UnsupportedParameters`.
+    pub fn from_parameters(parameters: &ParameterSet) -> Result<Self, VdfError> {
+        Ok(Self {
+            group: parameters.rug_group()?,
+            parameters: parameters.clone(),
+            ..Self::new()
+        })
+    }
+
+    /// Parameter set the engine computes and verifies with
+    pub fn parameters(&self) -> &ParameterSet {
+        &self.parameters
+    }
+
+    /// Refuse to compute or verify more than `max_iterations` squarings
+    pub fn with_max_iterations(mut self, max_iterations: u64) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Upper bound on iterations accepted by `compute` and `verify`
+    pub fn max_iterations(&self) -> u64 {
+        self.max_iterations
+    }
+
+    /// Group the engine squares in
+    pub fn group(&self) -> &RugRsaGroup {
+        &self.group
+    }
+
+    fn modulus(&self) -> &Integer {
+        self.group.modulus()
+    }
+
+    fn check_computable(&self) -> Result<(), VdfError> {
+        if !self.parameters.is_current() {
+            return Err(VdfError::UnsupportedParameters(format!(
+                "Parameter set {} is kept for verification only",
+                self.parameters.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn check_iterations(&self, iterations: u64) -> Result<(), VdfError> {
+        if iterations > self.max_iterations {
+            return Err(VdfError::TooManyIterations {
+                iterations,
+                max_iterations: self.max_iterations,
+            });
+        }
+        Ok(())
+    }
+
+    /// Save a snapshot every `snapshot_interval` squarings in `compute_persistent`
+    pub fn with_snapshot_interval(mut self, snapshot_interval: u64) -> Self {
+        self.snapshot_interval = snapshot_interval.max(1);
+        self
+    }
+
+    /// Produce proofs with the given scheme instead of Wesolowski
+    pub fn with_scheme(mut self, scheme: SchemeKind) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Use a custom checkpoint interval and memory budget for proof generation
+    pub fn with_checkpoint_config(mut self, config: CheckpointConfig) -> Self {
+        self.checkpoint_config = config;
+        self
+    }
+
+    /// Build proofs on up to `threads` worker threads
+    ///
+    /// The squaring chain stays on the calling thread. Once it finishes, the
+    /// Wesolowski proof is split over the workers by checkpoint, so it is
+    /// ready after roughly `1 / threads` of the single-threaded proving time;
+    /// each worker adds one bucket table to `memory_usage`. Pietrzak proofs
+    /// are always built on one thread.
+    ///
+    /// Proving does not overlap with squaring. Every quotient digit depends
+    /// on the challenge prime, which is hashed from the output, so no
+    /// checkpoint can be accumulated before the last squaring. The proof is
+    /// still a second pass after the chain, only a shorter one.
+    pub fn with_prover_threads(mut self, threads: usize) -> Self {
+        self.prover_threads = threads.max(1);
+        self
+    }
+
+    /// Worker threads used to build proofs
+    pub fn prover_threads(&self) -> usize {
+        self.prover_threads
+    }
+
+    /// Checkpoint layout that `compute` will use for `iterations`
+    pub fn checkpoint_plan(&self, iterations: u64) -> CheckpointPlan {
+        CheckpointPlan::new(iterations, &self.checkpoint_config)
+    }
+
+    /// Peak memory in bytes that `compute` spends on checkpoints and proof buckets
+    pub fn memory_usage(&self, iterations: u64) -> usize {
+        let threads = match self.scheme {
+            SchemeKind::Wesolowski => self.prover_threads,
+            SchemeKind::Pietrzak => 1,
+        };
+        self.checkpoint_plan(iterations).memory_usage_with_threads(threads)
     }
 
     /// Generate a unique VDF input seed from action details
     pub fn generate_input_seed(&self, input: &VDFInput) -> Integer {
-        let mut hasher = Sha256::new();
-        hasher.update(input.player_id.to_le_bytes());
-        hasher.update(input.action_type.as_bytes());
-        hasher.update(input.action_id.to_le_bytes());
-        hasher.update(input.timestamp.to_le_bytes());
-        hasher.update(input.nonce.to_le_bytes());
-        hasher.update(input.random_salt.to_le_bytes());
-        
-        let hash = hasher.finalize();
-        Integer::from_digits(&hash, rug::integer::Order::Lsf)
+        input_seed(input)
+    }
+
+    /// Input seed as a group element, `InvalidSeed` unless it is a valid
+    /// element other than `±1`
+    fn input_element(&self, input: &VDFInput) -> Result<Integer, VdfError> {
+        self.group.element_from_seed(&seed_digest(input))
     }
 
     /// Compute VDF output by repeated squaring
-    pub fn compute(&self, input: &VDFInput, iterations: u64) -> Result<VDFOutput, String> {
-        let start_time = SystemTime::now();
-        
-        // Generate input seed
-        let input_seed = self.generate_input_seed(input);
-        
-        // Ensure input is in valid range
-        if input_seed >= self.modulus {
-            return Err("Input seed must be less than modulus".to_string());
-        }
-        
-        // Compute output by repeated squaring: output = input^(2^iterations) mod modulus
-        let mut result = input_seed.clone();
-        for _ in 0..iterations {
-            result = result.square() % &self.modulus;
+    pub fn compute(&self, input: &VDFInput, iterations: u64) -> Result<VDFOutput, VdfError> {
+        match self.compute_with_progress(input, iterations, |_| {}, &CancellationToken::new())? {
+            ComputeStatus::Complete(output) => Ok(output),
+            ComputeStatus::Cancelled(_) => Err(VdfError::Cancelled),
         }
+    }
+
+    /// Compute VDF output, reporting progress and stopping when `cancel` is set
+    ///
+    /// `on_progress` is called every `PROGRESS_STEP` squarings and once the
+    /// squaring is done. A cancelled computation returns its partial state,
+    /// which `resume` continues.
+    pub fn compute_with_progress<F: FnMut(&Progress)>(&self, input: &VDFInput, iterations: u64, on_progress: F, cancel: &CancellationToken) -> Result<ComputeStatus, VdfError> {
+        let partial = self.start(input, iterations)?;
+        self.resume(partial, on_progress, cancel)
+    }
+
+    /// Fresh computation state with no squarings done
+    fn start(&self, input: &VDFInput, iterations: u64) -> Result<PartialComputation, VdfError> {
+        self.check_iterations(iterations)?;
         
-        let computation_time = start_time.elapsed()
-            .map_err(|e| format!("Time measurement error: {}", e))?
-            .as_secs_f64();
-        
-        // Generate Wesolowski proof
-        let proof = self.generate_proof(&input_seed, &result, iterations)?;
+        // Generate input seed, rejecting values outside the group and ±1
+        let input_seed = self.input_element(input)?;
         
-        Ok(VDFOutput {
+        Ok(PartialComputation {
             input: input.clone(),
-            output: result.to_string_radix(10),
-            iterations,
-            proof,
-            computation_time,
+            current: input_seed.clone(),
+            input_seed,
+            completed: 0,
+            trace: SquaringTrace::new(self.checkpoint_plan(iterations)),
+            elapsed: 0.0,
         })
     }
 
-    /// Generate Wesolowski proof for VDF computation
-    fn generate_proof(&self, input: &Integer, output: &Integer, iterations: u64) -> Result<String, String> {
-        // Hash input and output to generate challenge prime
-        let challenge = self.hash_to_prime(input, output)?;
-        
-        // Calculate remainder: r = 2^iterations mod l
-        let two = Integer::from(2);
-        let _remainder = two.clone().pow_mod(&Integer::from(iterations), &challenge)
-            .map_err(|e| format!("Modular exponentiation error: {}", e))?;
+    /// Continue a computation returned by a cancelled `compute_with_progress`
+    ///
+    /// The proof is produced with this engine's scheme; the checkpoint layout
+    /// stays the one the computation was started with.
+    pub fn resume<F: FnMut(&Progress)>(&self, partial: PartialComputation, on_progress: F, cancel: &CancellationToken) -> Result<ComputeStatus, VdfError> {
+        self.run(partial, on_progress, cancel, u64::MAX, |_| Ok(()))
+    }
+
+    /// Compute VDF output, saving a snapshot to `snapshot_path` every
+    /// `snapshot_interval` squarings and when cancelled
+    ///
+    /// An existing snapshot for the same input and iteration count is resumed.
+    /// The finished output is verified before it is returned and the snapshot
+    /// is removed. A proof that fails verification means the snapshot was
+    /// tampered with (see `snapshot`); the snapshot is removed as well, so the
+    /// next call starts over, and `InvalidSnapshot` is returned.
+    pub fn compute_persistent<F: FnMut(&Progress)>(&self, input: &VDFInput, iterations: u64, snapshot_path: &Path, on_progress: F, cancel: &CancellationToken) -> Result<ComputeStatus, VdfError> {
+        let partial = if snapshot_path.exists() {
+            let partial = self.load_snapshot(snapshot_path)?;
+            if partial.input != *input || partial.iterations() != iterations {
+                return Err(VdfError::InvalidSnapshot("Snapshot belongs to a different computation".to_string()));
+            }
+            partial
+        } else {
+            self.start(input, iterations)?
+        };
         
-        // Generate proof: pi = input^(2^iterations / l) mod modulus
-        let quotient = Integer::from(iterations) / &challenge;
-        let exponent = two.pow(quotient.to_u32().unwrap_or(0));
-        let proof = input.clone().pow_mod(&exponent, &self.modulus)
-            .map_err(|e| format!("Proof generation error: {}", e))?;
+        let status = self.run(partial, on_progress, cancel, self.snapshot_interval, |partial| partial.save(snapshot_path))?;
+        match &status {
+            ComputeStatus::Cancelled(partial) => partial.save(snapshot_path)?,
+            ComputeStatus::Complete(output) => {
+                let valid = self.verify_output(output)?;
+                std::fs::remove_file(snapshot_path).or_else(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => Ok(()),
+                    _ => Err(e),
+                })?;
+                if !valid {
+                    return Err(VdfError::InvalidSnapshot("Resumed computation produced an invalid proof".to_string()));
+                }
+            }
+        }
+        Ok(status)
+    }
+
+    /// Load a snapshot and check it against the squaring chain
+    ///
+    /// The first checkpoint must be the input seed and the current value must
+    /// follow from the last checkpoint, which costs at most one checkpoint
+    /// interval of squarings.
+    pub fn load_snapshot(&self, path: &Path) -> Result<PartialComputation, VdfError> {
+        let partial = PartialComputation::load(path)?;
+        self.check_iterations(partial.iterations())?;
+        if partial.input_seed >= *self.modulus()
+            || partial.current >= *self.modulus()
+            || partial.trace.checkpoints().iter().any(|checkpoint| *checkpoint >= *self.modulus())
+        {
+            return Err(VdfError::InvalidSnapshot("Value outside the group".to_string()));
+        }
         
-        Ok(proof.to_string_radix(10))
+        let (mut value, start) = match partial.trace.checkpoints().split_last() {
+            Some((last, _)) => {
+                if partial.trace.checkpoints()[0] != partial.input_seed {
+                    return Err(VdfError::InvalidSnapshot("First checkpoint is not the input seed".to_string()));
+                }
+                let index = partial.trace.checkpoints().len() as u64 - 1;
+                (last.clone(), index * partial.trace.plan().interval)
+            }
+            None => (partial.input_seed.clone(), 0),
+        };
+        for _ in start..partial.completed {
+            value = value.square() % self.modulus();
+        }
+        if value != partial.current {
+            return Err(VdfError::InvalidSnapshot("Current value does not follow from the checkpoints".to_string()));
+        }
+        Ok(partial)
     }
 
-    /// Verify Wesolowski proof
-    pub fn verify(&self, input: &Integer, output: &Integer, iterations: u64, proof: &Integer) -> Result<bool, String> {
-        // Recompute challenge
-        let challenge = self.hash_to_prime(input, output)?;
+    /// Square until done or cancelled, calling `persist` every `persist_every` squarings
+    fn run<F, P>(&self, mut partial: PartialComputation, mut on_progress: F, cancel: &CancellationToken, persist_every: u64, mut persist: P) -> Result<ComputeStatus, VdfError>
+    where
+        F: FnMut(&Progress),
+        P: FnMut(&PartialComputation) -> Result<(), VdfError>,
+    {
+        let iterations = partial.iterations();
+        self.check_computable()?;
+        self.check_iterations(iterations)?;
+        let start_time = SystemTime::now();
+        let session_start = partial.completed;
+        let previous_elapsed = partial.elapsed;
+        let report = |done: u64| -> Result<Progress, VdfError> {
+            let seconds = start_time.elapsed()?.as_secs_f64();
+            let rate = if seconds > 0.0 { (done - session_start) as f64 / seconds } else { 0.0 };
+            let eta_seconds = if rate > 0.0 { (iterations - done) as f64 / rate } else { f64::INFINITY };
+            Ok(Progress { iterations_done: done, total_iterations: iterations, rate, eta_seconds })
+        };
         
-        // Calculate remainder: r = 2^iterations mod l
-        let two = Integer::from(2);
-        let remainder = two.clone().pow_mod(&Integer::from(iterations), &challenge)
-            .map_err(|e| format!("Modular exponentiation error: {}", e))?;
+        // Compute output by repeated squaring: output = input^(2^iterations) mod modulus,
+        // keeping checkpoints so the proof does not have to redo the delay
+        for i in partial.completed..iterations {
+            if i > session_start {
+                if (i - session_start).is_multiple_of(PROGRESS_STEP) {
+                    on_progress(&report(i)?);
+                }
+                if (i - session_start).is_multiple_of(persist_every) {
+                    partial.completed = i;
+                    partial.elapsed = previous_elapsed + start_time.elapsed()?.as_secs_f64();
+                    persist(&partial)?;
+                }
+            }
+            if cancel.is_cancelled() {
+                partial.completed = i;
+                partial.elapsed = previous_elapsed + start_time.elapsed()?.as_secs_f64();
+                return Ok(ComputeStatus::Cancelled(partial));
+            }
+            partial.trace.observe(i, &partial.current);
+            partial.current = partial.current.square() % self.modulus();
+        }
+        on_progress(&report(iterations)?);
         
-        // Verify equation: input^r × pi^l ≡ output (mod modulus)
-        let left_side = (input.clone().pow_mod(&remainder, &self.modulus)
-            .map_err(|e| format!("Left side computation error: {}", e))?
-            * proof.clone().pow_mod(&challenge, &self.modulus)
-            .map_err(|e| format!("Right side computation error: {}", e))?) % &self.modulus;
+        let computation_time = previous_elapsed + start_time.elapsed()?.as_secs_f64();
         
-        Ok(left_side == *output)
+        // Generate proof with the configured scheme
+        let context = self.parameters.challenge_context(self.scheme, &partial.input.action_type);
+        let proof = self.scheme.scheme().prove_parallel(self.modulus(), &context, &partial.trace, &partial.input_seed, &partial.current, self.prover_threads)?;
+        
+        Ok(ComputeStatus::Complete(VDFOutput {
+            input: partial.input,
+            output: element_to_bytes(&self.group.canonical(&partial.current))?.to_vec(),
+            iterations,
+            proof: encode_elements(&proof)?,
+            computation_time,
+            scheme: self.scheme,
+            parameters: self.parameters.clone(),
+        }))
     }
 
-    /// Hash input and output to generate a prime challenge
-    fn hash_to_prime(&self, input: &Integer, output: &Integer) -> Result<Integer, String> {
-        let mut hasher = Sha256::new();
-        hasher.update(input.to_string_radix(10).as_bytes());
-        hasher.update(output.to_string_radix(10).as_bytes());
-        let hash = hasher.finalize();
-        
-        // Convert hash to integer and find next prime
-        let mut candidate = Integer::from_digits(&hash, rug::integer::Order::Lsf);
-        
-        // Ensure candidate is odd
-        if candidate.is_even() {
-            candidate += 1;
+    /// Run the delays of `actions` back to back with one aggregated proof
+    ///
+    /// Each `(input, iterations)` pair is one segment; every segment after
+    /// the first starts from a seed bound to the previous segment's output,
+    /// see `continuation`. `max_iterations` applies to the total.
+    pub fn compute_continuation(&self, actions: &[(VDFInput, u64)]) -> Result<ContinuationOutput, VdfError> {
+        let total = actions.iter()
+            .try_fold(0u64, |total, (_, iterations)| total.checked_add(*iterations))
+            .unwrap_or(u64::MAX);
+        self.check_computable()?;
+        self.check_iterations(total)?;
+        continuation::compute(&self.group, &self.parameters, &self.checkpoint_config, actions)
+    }
+
+    /// Verify the aggregated proof of a `ContinuationOutput`
+    ///
+    /// Like `verify_output`, continuations made with another parameter set
+    /// return `ParameterMismatch`.
+    pub fn verify_continuation(&self, output: &ContinuationOutput) -> Result<bool, VdfError> {
+        self.parameters.check_recorded(&output.parameters)?;
+        self.check_iterations(output.total_iterations().unwrap_or(u64::MAX))?;
+        continuation::verify_in(&self.group, output)
+    }
+
+    /// Evaluate the randomness beacon for `seed`, see `beacon`
+    pub fn run_beacon(&self, seed: EpochSeed, iterations: u64) -> Result<Beacon, VdfError> {
+        let output = self.compute(&beacon::beacon_input(&seed), iterations)?;
+        Ok(Beacon { seed, output })
+    }
+
+    /// Check a beacon before using its random values
+    pub fn verify_beacon(&self, beacon: &Beacon) -> Result<bool, VdfError> {
+        self.parameters.check_output(&beacon.output)?;
+        self.check_iterations(beacon.output.iterations)?;
+        beacon.verify_in(&self.group)
+    }
+
+    /// Verify Wesolowski proof for the action `input`
+    pub fn verify(&self, input: &VDFInput, output: &Integer, iterations: u64, proof: &Integer) -> Result<bool, VdfError> {
+        self.verify_with_scheme(SchemeKind::Wesolowski, input, output, iterations, std::slice::from_ref(proof))
+    }
+
+    /// Verify a `VDFOutput` as returned by `compute`
+    ///
+    /// The embedded input, iterations, output and proof are checked with the
+    /// scheme recorded in the output, see `verify_output_in`. Elements are
+    /// taken in the quotient group `{±1}`: anything but the canonical
+    /// representative `1 < x <= (N - 1) / 2` is rejected, so every valid
+    /// computation has exactly one encoding and `±1` never passes as an
+    /// output. A Wesolowski proof may also be `1`, the honest proof for
+    /// fewer than 256 iterations.
+    ///
+    /// Outputs made with another parameter set return `ParameterMismatch`;
+    /// verify those with `VDFEngine::from_parameters(&output.parameters)`.
+    pub fn verify_output(&self, output: &VDFOutput) -> Result<bool, VdfError> {
+        self.parameters.check_output(output)?;
+        self.check_iterations(output.iterations)?;
+        verify_output_in(&self.group, output)
+    }
+
+    /// Verify a `VDFOutput` and check its timestamp and anchor against `policy`
+    /// at the current time, see `policy`
+    pub fn verify_with_policy(&self, output: &VDFOutput, policy: &VerificationPolicy) -> Result<Verdict, VdfError> {
+        self.parameters.check_output(output)?;
+        self.check_iterations(output.iterations)?;
+        policy.verify_in(&self.group, output, policy::unix_now()?)
+    }
+
+    /// Verify many outputs at once, returning one `verify_output` result per entry
+    ///
+    /// Wesolowski proofs are checked together with a random linear combination
+    /// costing one shared multi-exponentiation; if that check fails, the batch
+    /// is split in halves until the failing proofs are found. Other schemes,
+    /// and all outputs of a set without the quotient group, are verified one
+    /// by one.
+    pub fn verify_batch(&self, outputs: &[VDFOutput]) -> Vec<Result<bool, VdfError>> {
+        let mut results: Vec<Result<bool, VdfError>> = Vec::with_capacity(outputs.len());
+        let mut batch = Vec::new();
+        for (index, output) in outputs.iter().enumerate() {
+            let entry = match (output.scheme, self.decode_output(output)) {
+                (SchemeKind::Wesolowski, Ok(Some((value, proof)))) if proof.len() == 1 && self.parameters.quotient_group() => {
+                    let proof = proof.into_iter().next().expect("Proof has one element");
+                    let context = self.parameters.challenge_context(SchemeKind::Wesolowski, &output.input.action_type);
+                    let input_seed = self.generate_input_seed(&output.input);
+                    wesolowski::BatchEntry::new(self.modulus(), &context, input_seed, value, output.iterations, proof)
+                }
+                _ => {
+                    results.push(self.verify_output(output));
+                    continue;
+                }
+            };
+            match entry {
+                Ok(entry) => {
+                    results.push(Ok(true));
+                    batch.push((index, entry));
+                }
+                Err(e) => results.push(Err(e)),
+            }
         }
         
-        // Find next prime
-        while candidate.is_probably_prime(10) != rug::integer::IsPrime::Yes {
-            candidate += 2;
+        let mut pending = vec![batch.as_slice()];
+        while let Some(group) = pending.pop() {
+            let valid = match group {
+                [] => true,
+                [(_, entry)] => entry.verify(self.modulus()),
+                _ => wesolowski::verify_batch(self.modulus(), &group.iter().map(|(_, entry)| entry).collect::<Vec<_>>()),
+            };
+            if valid {
+                continue;
+            }
+            if let [(index, _)] = group {
+                results[*index] = Ok(false);
+            } else {
+                let (first, second) = group.split_at(group.len() / 2);
+                pending.push(first);
+                pending.push(second);
+            }
         }
         
-        Ok(candidate)
+        results
+    }
+
+    /// Output and proof elements of `output`, or `None` if a value is not canonical
+    fn decode_output(&self, output: &VDFOutput) -> Result<Option<(Integer, Vec<Integer>)>, VdfError> {
+        self.parameters.check_output(output)?;
+        self.check_iterations(output.iterations)?;
+        self.input_element(&output.input)?;
+        
+        let value = output.output_element()?;
+        let proof = output.proof_elements()?;
+        if !self.group.is_valid(&value) || !proof.iter().all(|element| self.group.is_valid(element)) {
+            return Ok(None);
+        }
+        Ok(Some((value, proof)))
+    }
+
+    /// Verify a proof produced by any supported scheme for the action `input`
+    ///
+    /// The challenge covers the input seed, `output`, `iterations`, the scheme
+    /// and `input.action_type`, so the proof does not transfer to any other
+    /// statement. `output` and the proof elements must be canonical, see
+    /// `verify_output`; negative, zero, unreduced or `±1` values return
+    /// `false`, except for a Wesolowski proof of `1`.
+    pub fn verify_with_scheme(&self, scheme: SchemeKind, input: &VDFInput, output: &Integer, iterations: u64, proof: &[Integer]) -> Result<bool, VdfError> {
+        self.check_iterations(iterations)?;
+        let input_seed = self.input_element(input)?;
+        let context = self.parameters.challenge_context(scheme, &input.action_type);
+        group::verify_statement_in(&self.group, scheme, &context, &input_seed, output, iterations, proof)
     }
 
-    /// Convert time in seconds to VDF iterations
+    /// Iterations the protocol requires for an action of nominal duration `seconds`
     pub fn time_to_iterations(&self, seconds: u64) -> u64 {
-        seconds * ITERATIONS_PER_SECOND
+        seconds * self.parameters.iterations_per_second
     }
 
-    /// Convert VDF iterations to estimated time in seconds
+    /// Nominal protocol duration in seconds of `iterations`
     pub fn iterations_to_time(&self, iterations: u64) -> u64 {
-        iterations / ITERATIONS_PER_SECOND
+        iterations / self.parameters.iterations_per_second
+    }
+
+    /// Use a measured profile for local wall-clock estimates
+    pub fn with_calibration(mut self, calibration: CalibrationProfile) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    pub fn calibration(&self) -> Option<&CalibrationProfile> {
+        self.calibration.as_ref()
+    }
+
+    /// Expected wall-clock seconds for `iterations` on this machine, or
+    /// `None` without a calibration profile
+    pub fn estimated_local_time(&self, iterations: u64) -> Option<f64> {
+        self.calibration.as_ref().map(|profile| profile.expected_seconds(iterations))
+    }
+
+    /// Mismatch between the nominal duration of `iterations` under this
+    /// engine's parameter set and their local duration, if any
+    pub fn timing_mismatch(&self, iterations: u64) -> Option<TimingMismatch> {
+        self.calibration.as_ref().and_then(|profile| profile.check(iterations, &self.parameters))
     }
 
     /// Benchmark VDF computation speed
-    pub fn benchmark(&self, test_iterations: u64) -> Result<f64, String> {
+    pub fn benchmark(&self, test_iterations: u64) -> Result<f64, VdfError> {
         let test_input = VDFInput {
             player_id: 1,
             action_type: "benchmark".to_string(),
             action_id: 1,
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)
-                .map_err(|e| format!("Time error: {}", e))?
-                .as_secs(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
             nonce: 1,
             random_salt: 12345,
         };
         
         let start_time = SystemTime::now();
         self.compute(&test_input, test_iterations)?;
-        let elapsed = start_time.elapsed()
-            .map_err(|e| format!("Time measurement error: {}", e))?
-            .as_secs_f64();
+        let elapsed = start_time.elapsed()?.as_secs_f64();
         
         Ok(test_iterations as f64 / elapsed)
     }
 }
 
+/// Hash the action details into the 256-bit seed shared by all backends,
+/// read as a little-endian integer by `VdfGroup::element_from_seed`
+pub(crate) fn seed_digest(input: &VDFInput) -> [u8; 32] {
+    input.seed_digest()
+}
+
+/// `seed_digest` as an integer
+#[cfg(feature = "gmp")]
+pub(crate) fn input_seed(input: &VDFInput) -> Integer {
+    Integer::from_digits(&seed_digest(input), rug::integer::Order::Lsf)
+}
+
+#[cfg(feature = "gmp")]
 impl Default for VDFEngine {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "gmp"))]
 mod tests {
     use super::*;
 
@@ -233,22 +667,606 @@ mod tests {
         };
         
         let result = engine.compute(&input, 1000).expect("VDF computation failed");
-        let input_seed = engine.generate_input_seed(&input);
-        let output = Integer::from_str_radix(&result.output, 10).expect("Failed to parse output");
-        let proof = Integer::from_str_radix(&result.proof, 10).expect("Failed to parse proof");
+        let output = result.output_element().expect("Failed to parse output");
+        let proof = element_from_bytes(&result.proof).expect("Failed to parse proof");
         
-        let is_valid = engine.verify(&input_seed, &output, 1000, &proof)
+        let is_valid = engine.verify(&input, &output, 1000, &proof)
             .expect("Verification failed");
         assert!(is_valid);
     }
 
+    #[test]
+    fn test_verify_output() {
+        let engine = VDFEngine::new();
+        let input = VDFInput {
+            player_id: 5,
+            action_type: "craft".to_string(),
+            action_id: 2,
+            timestamp: 1234567890,
+            nonce: 8,
+            random_salt: 4242,
+        };
+        
+        let result = engine.compute(&input, 1000).expect("VDF computation failed");
+        assert!(engine.verify_output(&result).expect("Verification failed"));
+        
+        let mut wrong_iterations = result.clone();
+        wrong_iterations.iterations = 999;
+        assert!(!engine.verify_output(&wrong_iterations).expect("Verification failed"));
+        
+        let mut wrong_input = result.clone();
+        wrong_input.input.nonce += 1;
+        assert!(!engine.verify_output(&wrong_input).expect("Verification failed"));
+        
+        let mut wrong_output = result.clone();
+        wrong_output.output[ELEMENT_BYTES - 1] ^= 1;
+        assert!(!engine.verify_output(&wrong_output).expect("Verification failed"));
+        
+        // 0 = input^r × 0^l holds for every statement, so zero is not a group element
+        let mut zero = result.clone();
+        zero.output = vec![0; ELEMENT_BYTES];
+        zero.proof = vec![0; ELEMENT_BYTES];
+        assert!(!engine.verify_output(&zero).expect("Verification failed"));
+        assert!(!verify_output_in(&BigUintRsaGroup::rsa_2048(), &zero).expect("Verification failed"));
+        
+        let mut truncated_proof = result.clone();
+        truncated_proof.proof.pop();
+        assert!(matches!(engine.verify_output(&truncated_proof), Err(VdfError::MalformedProof(_))));
+        
+        let pietrzak = VDFEngine::new().with_scheme(SchemeKind::Pietrzak)
+            .compute(&input, 1000).expect("VDF computation failed");
+        assert!(engine.verify_output(&pietrzak).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_pure_rust_backend_verifies_engine_outputs() {
+        let group = BigUintRsaGroup::rsa_2048();
+        let input = VDFInput {
+            player_id: 3,
+            action_type: "craft".to_string(),
+            action_id: 12,
+            timestamp: 1234567890,
+            nonce: 6,
+            random_salt: 5150,
+        };
+        for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+            let result = VDFEngine::new().with_scheme(scheme).compute(&input, 700).expect("VDF computation failed");
+            assert!(verify_output_in(&group, &result).expect("Verification failed"), "{:?} output rejected", scheme);
+            
+            let mut replayed = result.clone();
+            replayed.iterations = 701;
+            assert!(!verify_output_in(&group, &replayed).expect("Verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_finds_failures() {
+        let engine = VDFEngine::new();
+        let mut outputs: Vec<VDFOutput> = (0..6)
+            .map(|action_id| {
+                let input = VDFInput {
+                    player_id: 1,
+                    action_type: "craft".to_string(),
+                    action_id,
+                    timestamp: 1234567890,
+                    nonce: 1,
+                    random_salt: 12345,
+                };
+                engine.compute(&input, 300).expect("VDF computation failed")
+            })
+            .collect();
+        assert!(engine.verify_batch(&outputs).iter().all(|result| result == &Ok(true)));
+        
+        outputs[1].iterations = 299;
+        outputs[4].proof = outputs[3].proof.clone();
+        outputs[5].proof.pop();
+        let pietrzak_input = outputs[2].input.clone();
+        outputs.push(VDFEngine::new().with_scheme(SchemeKind::Pietrzak).compute(&pietrzak_input, 300).expect("VDF computation failed"));
+        
+        let results = engine.verify_batch(&outputs);
+        assert_eq!(results.len(), outputs.len());
+        for (index, result) in results.iter().enumerate() {
+            match index {
+                1 | 4 => assert_eq!(result, &Ok(false), "entry {} should fail", index),
+                5 => assert!(matches!(result, Err(VdfError::MalformedProof(_)))),
+                _ => assert_eq!(result, &Ok(true), "entry {} should pass", index),
+            }
+        }
+    }
+
+    #[test]
+    fn test_proof_cannot_be_replayed_with_other_iterations() {
+        let input = VDFInput {
+            player_id: 9,
+            action_type: "craft".to_string(),
+            action_id: 1,
+            timestamp: 1234567890,
+            nonce: 5,
+            random_salt: 777,
+        };
+        for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+            let engine = VDFEngine::new().with_scheme(scheme);
+            let result = engine.compute(&input, 1000).expect("VDF computation failed");
+            assert!(engine.verify_output(&result).expect("Verification failed"));
+            
+            for iterations in [999, 2000] {
+                let mut replayed = result.clone();
+                replayed.iterations = iterations;
+                assert!(!engine.verify_output(&replayed).expect("Verification failed"),
+                    "{:?} proof for 1000 iterations accepted for {}", scheme, iterations);
+            }
+            
+            // Same squaring chain, claimed under the other scheme's label
+            let mut relabelled = result.clone();
+            relabelled.scheme = match scheme {
+                SchemeKind::Wesolowski => SchemeKind::Pietrzak,
+                SchemeKind::Pietrzak => SchemeKind::Wesolowski,
+            };
+            assert!(!engine.verify_output(&relabelled).expect("Verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_proof_is_bound_to_action_type() {
+        let engine = VDFEngine::new();
+        let input = VDFInput {
+            player_id: 9,
+            action_type: "craft".to_string(),
+            action_id: 1,
+            timestamp: 1234567890,
+            nonce: 5,
+            random_salt: 777,
+        };
+        let result = engine.compute(&input, 1000).expect("VDF computation failed");
+        let input_seed = engine.generate_input_seed(&input);
+        let output = result.output_element().expect("Failed to parse output");
+        let proof = result.proof_elements().expect("Failed to parse proof");
+        
+        // Even with the seed and output unchanged, another action type changes the challenge
+        let scheme = SchemeKind::Wesolowski.scheme();
+        let craft = challenge_context(SchemeKind::Wesolowski, "craft");
+        let gather = challenge_context(SchemeKind::Wesolowski, "gather");
+        assert!(scheme.verify(engine.modulus(), &craft, &input_seed, &output, 1000, &proof).expect("Verification failed"));
+        assert!(!scheme.verify(engine.modulus(), &gather, &input_seed, &output, 1000, &proof).expect("Verification failed"));
+        
+        let mut other_action = result.clone();
+        other_action.input.action_type = "gather".to_string();
+        assert!(!engine.verify_output(&other_action).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_vdf_proof_matches_direct_quotient() {
+        let engine = VDFEngine::new();
+        let input = VDFInput {
+            player_id: 7,
+            action_type: "craft".to_string(),
+            action_id: 3,
+            timestamp: 1234567890,
+            nonce: 2,
+            random_salt: 999,
+        };
+        
+        let iterations = 300;
+        let result = engine.compute(&input, iterations).expect("VDF computation failed");
+        let input_seed = engine.generate_input_seed(&input);
+        let output = result.output_element().expect("Failed to parse output");
+        let proof = element_from_bytes(&result.proof).expect("Failed to parse proof");
+        
+        // Small enough to build floor(2^T / l) explicitly and compare
+        let challenge = wesolowski::challenge(engine.modulus(), iterations, &challenge_context(SchemeKind::Wesolowski, "craft"), &input_seed, &output).expect("Hash to prime failed");
+        let quotient = (Integer::from(1) << iterations as u32) / &challenge;
+        let expected = input_seed.pow_mod(&quotient, engine.modulus()).expect("Exponentiation failed");
+        assert_eq!(proof, engine.group().canonical(&expected));
+    }
+
+    #[test]
+    fn test_short_delays_verify_with_identity_proof() {
+        let engine = VDFEngine::new();
+        let group = BigUintRsaGroup::rsa_2048();
+        let input = VDFInput {
+            player_id: 7,
+            action_type: "craft".to_string(),
+            action_id: 4,
+            timestamp: 1234567890,
+            nonce: 2,
+            random_salt: 999,
+        };
+
+        for iterations in [0, 1, 2, 10, 100, 200, 255] {
+            let result = engine.compute(&input, iterations).expect("VDF computation failed");
+            // 2^T is below the 256-bit challenge, so floor(2^T / l) = 0
+            assert_eq!(element_from_bytes(&result.proof).expect("Failed to parse proof"), 1, "T = {}", iterations);
+            assert!(engine.verify_output(&result).expect("Verification failed"), "T = {} rejected", iterations);
+            assert!(verify_output_in(&group, &result).expect("Verification failed"), "T = {} rejected", iterations);
+            assert!(matches!(engine.verify_batch(std::slice::from_ref(&result))[..], [Ok(true)]), "T = {} rejected", iterations);
+
+            let mut replayed = result.clone();
+            replayed.iterations = iterations + 1;
+            assert!(!engine.verify_output(&replayed).expect("Verification failed"));
+        }
+    }
+
+    #[test]
+    fn test_vdf_rejects_wrong_output() {
+        let engine = VDFEngine::new();
+        let input = VDFInput {
+            player_id: 1,
+            action_type: "test".to_string(),
+            action_id: 1,
+            timestamp: 1234567890,
+            nonce: 1,
+            random_salt: 12345,
+        };
+        
+        let result = engine.compute(&input, 2000).expect("VDF computation failed");
+        let output = result.output_element().expect("Failed to parse output");
+        let proof = element_from_bytes(&result.proof).expect("Failed to parse proof");
+        
+        assert!(engine.verify(&input, &output, 2000, &proof).expect("Verification failed"));
+        assert!(!engine.verify(&input, &output, 1999, &proof).expect("Verification failed"));
+        
+        let wrong_output = (output.clone() + 1u32) % engine.modulus();
+        assert!(!engine.verify(&input, &wrong_output, 2000, &proof).expect("Verification failed"));
+        
+        // Other representatives of the same classes are not canonical
+        let negated_output = Integer::from(engine.modulus() - &output);
+        let negated_proof = Integer::from(engine.modulus() - &proof);
+        assert!(!engine.verify(&input, &negated_output, 2000, &negated_proof).expect("Verification failed"));
+        assert!(!engine.verify(&input, &Integer::from(-&output), 2000, &Integer::from(-&proof)).expect("Verification failed"));
+        assert!(!engine.verify(&input, &Integer::from(&output + engine.modulus()), 2000, &proof).expect("Verification failed"));
+    }
+
+    /// Wesolowski output the way parameter set `rsa2048-v1` produced it:
+    /// plain residues and statement version 1
+    fn legacy_output(input: &VDFInput, iterations: u64) -> VDFOutput {
+        let parameters = ParameterSet::legacy();
+        let group = parameters.rug_group().expect("Published set");
+        let modulus = group.modulus();
+        let input_seed = group.element_from_seed(&seed_digest(input)).expect("Seed out of range");
+        let output = input_seed.clone().pow_mod(&(Integer::from(1) << iterations as u32), modulus).expect("Exponentiation failed");
+        let context = parameters.challenge_context(SchemeKind::Wesolowski, &input.action_type);
+        let challenge = wesolowski::challenge(modulus, iterations, &context, &input_seed, &output).expect("Hash to prime failed");
+        let quotient = (Integer::from(1) << iterations as u32) / &challenge;
+        let proof = input_seed.pow_mod(&quotient, modulus).expect("Exponentiation failed");
+        VDFOutput {
+            input: input.clone(),
+            output: element_to_bytes(&output).expect("Encoding failed").to_vec(),
+            iterations,
+            proof: element_to_bytes(&proof).expect("Encoding failed").to_vec(),
+            computation_time: 0.0,
+            scheme: SchemeKind::Wesolowski,
+            parameters,
+        }
+    }
+
+    #[test]
+    fn test_legacy_outputs_stay_verifiable() {
+        let input = VDFInput {
+            player_id: 8,
+            action_type: "craft".to_string(),
+            action_id: 4,
+            timestamp: 1234567890,
+            nonce: 7,
+            random_salt: 2468,
+        };
+        let legacy = legacy_output(&input, 300);
+        let engine = VDFEngine::from_parameters(&legacy.parameters).expect("Published set");
+        assert!(engine.verify_output(&legacy).expect("Verification failed"));
+        assert_eq!(engine.verify_batch(std::slice::from_ref(&legacy)), vec![Ok(true)]);
+        assert!(ParameterSet::legacy().verify_output(&legacy).expect("Verification failed"));
+
+        // A proof saved as JSON before sets were recorded has no `parameters`
+        let mut json = serde_json::to_value(&legacy).expect("Serialization failed");
+        json.as_object_mut().expect("Output is an object").remove("parameters");
+        let saved: VDFOutput = serde_json::from_value(json).expect("Deserialization failed");
+        assert!(engine.verify_output(&saved).expect("Verification failed"));
+
+        // The current engine refuses to mix sets, in both directions
+        let current = VDFEngine::new();
+        assert!(matches!(current.verify_output(&legacy), Err(VdfError::ParameterMismatch { .. })));
+        let fresh = current.compute(&input, 300).expect("VDF computation failed");
+        assert!(matches!(engine.verify_output(&fresh), Err(VdfError::ParameterMismatch { .. })));
+        assert!(matches!(current.verify_batch(&[legacy.clone(), fresh.clone()]).as_slice(),
+            [Err(VdfError::ParameterMismatch { .. }), Ok(true)]));
+
+        // Relabelling the legacy proof as current fails verification
+        let mut relabelled = legacy.clone();
+        relabelled.parameters = ParameterSet::current();
+        assert!(!current.verify_output(&relabelled).expect("Verification failed"));
+
+        // Legacy sets are verify-only
+        assert!(matches!(engine.compute(&input, 300), Err(VdfError::UnsupportedParameters(_))));
+        let mut edited = ParameterSet::current();
+        edited.iterations_per_second = 1;
+        assert!(VDFEngine::from_parameters(&edited).is_err());
+    }
+
+    #[test]
+    fn test_vdf_checkpoint_intervals_agree() {
+        let input = VDFInput {
+            player_id: 3,
+            action_type: "craft".to_string(),
+            action_id: 9,
+            timestamp: 1234567890,
+            nonce: 4,
+            random_salt: 777,
+        };
+        
+        let iterations = 1500;
+        let reference = VDFEngine::new().compute(&input, iterations).expect("VDF computation failed");
+        for interval in [1, 7, 64, 1000, 5000] {
+            let engine = VDFEngine::new().with_checkpoint_config(CheckpointConfig {
+                interval: Some(interval),
+                ..CheckpointConfig::default()
+            });
+            let result = engine.compute(&input, iterations).expect("VDF computation failed");
+            assert_eq!(result.output, reference.output);
+            assert_eq!(result.proof, reference.proof, "interval {} produced a different proof", interval);
+        }
+        
+        let engine = VDFEngine::new();
+        let output = reference.output_element().expect("Failed to parse output");
+        let proof = element_from_bytes(&reference.proof).expect("Failed to parse proof");
+        assert!(engine.verify(&input, &output, iterations, &proof).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_parallel_prover_matches_sequential() {
+        let input = VDFInput {
+            player_id: 5,
+            action_type: "craft".to_string(),
+            action_id: 11,
+            timestamp: 1234567890,
+            nonce: 6,
+            random_salt: 31337,
+        };
+        
+        let iterations = 1500;
+        for interval in [7, 64] {
+            let config = CheckpointConfig {
+                interval: Some(interval),
+                ..CheckpointConfig::default()
+            };
+            let reference = VDFEngine::new().with_checkpoint_config(config.clone())
+                .compute(&input, iterations).expect("VDF computation failed");
+            for threads in [2, 3, 8, 1000] {
+                let engine = VDFEngine::new().with_checkpoint_config(config.clone()).with_prover_threads(threads);
+                let result = engine.compute(&input, iterations).expect("VDF computation failed");
+                assert_eq!(result.proof, reference.proof, "{} threads produced a different proof", threads);
+                assert!(engine.verify_output(&result).expect("Verification failed"));
+            }
+        }
+        
+        let engine = VDFEngine::new().with_scheme(SchemeKind::Pietrzak).with_prover_threads(4);
+        let result = engine.compute(&input, iterations).expect("VDF computation failed");
+        assert!(engine.verify_output(&result).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_memory_usage_follows_budget() {
+        let budget = 4 * 1024 * 1024;
+        let engine = VDFEngine::new().with_checkpoint_config(CheckpointConfig {
+            interval: None,
+            memory_budget: budget,
+        });
+        
+        let one_hour = engine.time_to_iterations(3600);
+        assert!(engine.memory_usage(one_hour) <= budget);
+        assert!(engine.memory_usage(one_hour) >= engine.memory_usage(10));
+    }
+
+    #[test]
+    fn test_pietrzak_scheme_round_trip() {
+        let engine = VDFEngine::new().with_scheme(SchemeKind::Pietrzak);
+        let input = VDFInput {
+            player_id: 1,
+            action_type: "gather".to_string(),
+            action_id: 2,
+            timestamp: 1234567890,
+            nonce: 1,
+            random_salt: 12345,
+        };
+        
+        let result = engine.compute(&input, 1000).expect("VDF computation failed");
+        assert_eq!(result.scheme, SchemeKind::Pietrzak);
+        
+        let output = result.output_element().expect("Failed to parse output");
+        let proof = result.proof_elements().expect("Failed to parse proof");
+        
+        assert!(engine.verify_with_scheme(SchemeKind::Pietrzak, &input, &output, 1000, &proof)
+            .expect("Verification failed"));
+        assert!(!engine.verify_with_scheme(SchemeKind::Wesolowski, &input, &output, 1000, &proof)
+            .expect("Verification failed"));
+        
+        // Both schemes attest the same squaring chain
+        let wesolowski = VDFEngine::new().compute(&input, 1000).expect("VDF computation failed");
+        assert_eq!(wesolowski.output, result.output);
+    }
+
+    #[test]
+    fn test_cancel_and_resume() {
+        let engine = VDFEngine::new();
+        let input = VDFInput {
+            player_id: 2,
+            action_type: "craft".to_string(),
+            action_id: 6,
+            timestamp: 1234567890,
+            nonce: 3,
+            random_salt: 31337,
+        };
+        let iterations = 3 * PROGRESS_STEP + 500;
+        
+        // Cancel from the callback after the second report
+        let cancel = CancellationToken::new();
+        let mut reports = Vec::new();
+        let status = engine.compute_with_progress(&input, iterations, |progress| {
+            reports.push(*progress);
+            if reports.len() == 2 {
+                cancel.cancel();
+            }
+        }, &cancel).expect("VDF computation failed");
+        let partial = match status {
+            ComputeStatus::Cancelled(partial) => partial,
+            ComputeStatus::Complete(_) => panic!("Computation should have been cancelled"),
+        };
+        assert_eq!(partial.completed(), 2 * PROGRESS_STEP);
+        assert_eq!(reports[1].iterations_done, 2 * PROGRESS_STEP);
+        assert!(reports[1].fraction() < 1.0);
+        
+        let mut last = None;
+        let status = engine.resume(partial, |progress| last = Some(*progress), &CancellationToken::new())
+            .expect("VDF computation failed");
+        let resumed = match status {
+            ComputeStatus::Complete(output) => output,
+            ComputeStatus::Cancelled(_) => panic!("Computation should have finished"),
+        };
+        assert_eq!(last.map(|progress| progress.iterations_done), Some(iterations));
+        
+        let direct = engine.compute(&input, iterations).expect("VDF computation failed");
+        assert_eq!(resumed.output, direct.output);
+        assert_eq!(resumed.proof, direct.proof);
+        assert!(engine.verify_output(&resumed).expect("Verification failed"));
+    }
+
+    #[test]
+    fn test_persistent_computation_survives_restart() {
+        let engine = VDFEngine::new().with_snapshot_interval(700);
+        let input = VDFInput {
+            player_id: 4,
+            action_type: "craft".to_string(),
+            action_id: 11,
+            timestamp: 1234567890,
+            nonce: 2,
+            random_salt: 2024,
+        };
+        let iterations = 2 * PROGRESS_STEP + 100;
+        let path = std::env::temp_dir().join(format!("zkgame-vdf-{}-restart.snapshot", std::process::id()));
+        
+        // The first run dies after the first progress report
+        let cancel = CancellationToken::new();
+        let status = engine.compute_persistent(&input, iterations, &path, |_| cancel.cancel(), &cancel)
+            .expect("VDF computation failed");
+        assert!(matches!(status, ComputeStatus::Cancelled(_)));
+        let saved = engine.load_snapshot(&path).expect("Snapshot should load");
+        assert_eq!(saved.completed(), PROGRESS_STEP);
+        
+        // A snapshot for another input is refused
+        let mut other = input.clone();
+        other.nonce += 1;
+        assert!(matches!(
+            engine.compute_persistent(&other, iterations, &path, |_| {}, &CancellationToken::new()),
+            Err(VdfError::InvalidSnapshot(_))
+        ));
+        
+        // A consistent-looking but edited current value is caught on load
+        let mut edited = saved.clone();
+        edited.current = (edited.current + 1u32) % engine.modulus();
+        edited.save(&path).expect("Failed to write snapshot");
+        assert!(matches!(engine.load_snapshot(&path), Err(VdfError::InvalidSnapshot(_))));
+        saved.save(&path).expect("Failed to write snapshot");
+        
+        let status = engine.compute_persistent(&input, iterations, &path, |_| {}, &CancellationToken::new())
+            .expect("VDF computation failed");
+        let output = match status {
+            ComputeStatus::Complete(output) => output,
+            ComputeStatus::Cancelled(_) => panic!("Computation should have finished"),
+        };
+        assert!(!path.exists());
+        assert_eq!(output.output, engine.compute(&input, iterations).expect("VDF computation failed").output);
+    }
+
+    #[test]
+    fn test_tampered_checkpoint_is_caught_by_the_final_proof() {
+        let engine = VDFEngine::new().with_checkpoint_config(CheckpointConfig {
+            interval: Some(100),
+            ..CheckpointConfig::default()
+        });
+        let input = VDFInput {
+            player_id: 4,
+            action_type: "craft".to_string(),
+            action_id: 12,
+            timestamp: 1234567890,
+            nonce: 2,
+            random_salt: 2025,
+        };
+        let iterations = 2 * PROGRESS_STEP;
+        let path = std::env::temp_dir().join(format!("zkgame-vdf-{}-tampered.snapshot", std::process::id()));
+        
+        let cancel = CancellationToken::new();
+        let status = engine.compute_persistent(&input, iterations, &path, |_| cancel.cancel(), &cancel)
+            .expect("VDF computation failed");
+        assert!(matches!(status, ComputeStatus::Cancelled(_)));
+        let saved = engine.load_snapshot(&path).expect("Snapshot should load");
+        
+        // A checkpoint between the first and the last is not checked on load
+        let mut checkpoints = saved.trace.checkpoints().to_vec();
+        assert!(checkpoints.len() > 2);
+        checkpoints[1] = checkpoints[1].clone().square() % engine.modulus();
+        let mut tampered = saved.clone();
+        tampered.trace = SquaringTrace::from_parts(*saved.trace.plan(), checkpoints);
+        tampered.save(&path).expect("Failed to write snapshot");
+        assert!(engine.load_snapshot(&path).is_ok());
+        
+        // Only the finished proof shows it, and the snapshot is gone afterwards
+        assert!(matches!(
+            engine.compute_persistent(&input, iterations, &path, |_| {}, &CancellationToken::new()),
+            Err(VdfError::InvalidSnapshot(_))
+        ));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_iteration_limit() {
+        let engine = VDFEngine::new().with_max_iterations(500);
+        let input = VDFInput {
+            player_id: 1,
+            action_type: "test".to_string(),
+            action_id: 1,
+            timestamp: 1234567890,
+            nonce: 1,
+            random_salt: 12345,
+        };
+        
+        let error = engine.compute(&input, 501).expect_err("Computation above the limit should fail");
+        assert_eq!(error, VdfError::TooManyIterations { iterations: 501, max_iterations: 500 });
+        
+        let input_seed = engine.generate_input_seed(&input);
+        let error = engine.verify(&input, &input_seed, 501, &input_seed)
+            .expect_err("Verification above the limit should fail");
+        assert!(matches!(error, VdfError::TooManyIterations { .. }));
+        
+        assert!(engine.compute(&input, 500).is_ok());
+    }
+
+    #[test]
+    fn test_calibration_is_separate_from_protocol_iterations() {
+        let profile = CalibrationProfile {
+            version: calibration::CALIBRATION_VERSION,
+            iterations_per_second: 50_000.0,
+            sample_iterations: 1000,
+            measured_at: 0,
+        };
+        let uncalibrated = VDFEngine::new();
+        let engine = VDFEngine::new().with_calibration(profile);
+        
+        // Required iterations do not depend on the local machine
+        assert_eq!(engine.time_to_iterations(3600), uncalibrated.time_to_iterations(3600));
+        assert_eq!(uncalibrated.estimated_local_time(1000), None);
+        assert!(uncalibrated.timing_mismatch(1000).is_none());
+        
+        let iterations = engine.time_to_iterations(3600);
+        let local = engine.estimated_local_time(iterations).expect("Calibrated engine should estimate");
+        assert!((local - iterations as f64 / 50_000.0).abs() < 1e-9);
+        assert!(engine.timing_mismatch(iterations).is_some());
+    }
+
     #[test]
     fn test_time_conversion() {
         let engine = VDFEngine::new();
         
         let seconds = 3600; // 1 hour
         let iterations = engine.time_to_iterations(seconds);
-        assert_eq!(iterations, 3600 * ITERATIONS_PER_SECOND);
+        assert_eq!(iterations, 3600 * PROTOCOL_ITERATIONS_PER_SECOND);
         
         let back_to_seconds = engine.iterations_to_time(iterations);
         assert_eq!(back_to_seconds, seconds);
@@ -0,0 +1,279 @@
+//! Named parameter sets carried in every `VDFOutput`
+//!
+//! A parameter set fixes everything a proof depends on besides the action
+//! itself: the group (`modulus_id`), the hash-to-prime version, the statement
+//! version bound into challenges, the security level and the protocol rate of
+//! iterations per nominal second. Changing any of these publishes a new set
+//! under a new name instead of silently invalidating saved proofs:
+//!
+//! | Set          | Group                        | Hash | Statement | Status      |
+//! |--------------|------------------------------|------|-----------|-------------|
+//! | `rsa2048-v1` | RSA-2048, `(Z/NZ)*`          | 2    | 1         | verify only |
+//! | `rsa2048-v2` | RSA-2048, `(Z/NZ)* / {±1}`   | 2    | 2         | current     |
+//!
+//! Outputs written before sets were recorded (JSON without `parameters`,
+//! binary format version 1) belong to `rsa2048-v1`. A `VDFEngine` only
+//! verifies outputs of its own set, see `VDFEngine::from_parameters`.
+//!
+//! The published values live in `zkgame_vdf_core::params`, which the
+//! WebAssembly verifier reads as well.
+
+use serde::{Serialize, Deserialize};
+use zkgame_vdf_core::params::{PublishedSet, PUBLISHED_SETS, RSA2048_V1, RSA2048_V2};
+
+use crate::error::VdfError;
+use crate::group::verify_output_in;
+use crate::hash_to_prime::HASH_TO_PRIME_VERSION;
+use crate::rsa_group::BigUintRsaGroup;
+#[cfg(feature = "gmp")]
+use crate::rsa_group::RugRsaGroup;
+use crate::scheme::{challenge_context_for_version, SchemeKind};
+use crate::{VDFOutput, PROTOCOL_ITERATIONS_PER_SECOND};
+
+pub use zkgame_vdf_core::params::{CURRENT_PARAMETER_SET, LEGACY_PARAMETER_SET, RSA_2048_MODULUS_ID};
+
+/// Prefix of class group set names, followed by `<bits>-<hex digest>`
+pub const CLASS_GROUP_PREFIX: &str = "class-group-";
+
+/// Protocol parameters a proof was made with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterSet {
+    /// Registry name, e.g. `rsa2048-v2`
+    pub name: String,
+    /// Group the squaring chain lives in
+    pub modulus_id: String,
+    /// `HASH_TO_PRIME_VERSION` of the challenge derivation
+    pub hash_to_prime_version: u8,
+    /// Statement version bound into challenges, see `challenge_context`
+    pub proof_version: u8,
+    /// Estimated security level of the group in bits
+    pub security_bits: u32,
+    /// Protocol rate mapping nominal seconds to iterations
+    pub iterations_per_second: u64,
+}
+
+impl ParameterSet {
+    /// RSA-2048 in `(Z/NZ)*` with statement version 1
+    pub fn rsa2048_v1() -> Self {
+        Self::from(&RSA2048_V1)
+    }
+
+    /// RSA-2048 in the quotient group `{±1}` with statement version 2
+    pub fn rsa2048_v2() -> Self {
+        Self::from(&RSA2048_V2)
+    }
+
+    /// The set new outputs are computed with
+    pub fn current() -> Self {
+        Self::rsa2048_v2()
+    }
+
+    /// The set of outputs that carry no parameters
+    pub fn legacy() -> Self {
+        Self::rsa2048_v1()
+    }
+
+    /// Every published set, oldest first
+    pub fn known() -> Vec<Self> {
+        PUBLISHED_SETS.iter().map(Self::from).collect()
+    }
+
+    /// Published set called `name`
+    pub fn by_name(name: &str) -> Result<Self, VdfError> {
+        zkgame_vdf_core::params::published_set(name)
+            .map(Self::from)
+            .ok_or_else(|| VdfError::UnsupportedParameters(format!("Unknown parameter set {}", name)))
+    }
+
+    /// Set of a `ClassGroupVdf` with a `discriminant_bits`-bit discriminant
+    /// whose SHA-256 digest is `discriminant_digest`, see `ClassGroup::digest`
+    ///
+    /// Class groups are seeded per season and not published. Name and
+    /// `modulus_id` are `class-group-<bits>-<hex digest>`, so outputs of
+    /// different seasons never share a set. The security level scales Hamdy
+    /// and Möller's estimate of 128 bits for 1665-bit discriminants and is
+    /// only a rough figure.
+    pub fn class_group(discriminant_bits: u32, discriminant_digest: &[u8; 32]) -> Self {
+        let id = format!("{}{}-{}", CLASS_GROUP_PREFIX, discriminant_bits, hex::encode(discriminant_digest));
+        Self {
+            name: id.clone(),
+            modulus_id: id,
+            hash_to_prime_version: HASH_TO_PRIME_VERSION,
+            proof_version: crate::PROOF_VERSION,
+            security_bits: (discriminant_bits as u64 * 128 / 1665) as u32,
+            iterations_per_second: PROTOCOL_ITERATIONS_PER_SECOND,
+        }
+    }
+
+    /// Set recorded under `name` in a binary encoding: a published set or a
+    /// class group set
+    pub(crate) fn from_name(name: &str) -> Result<Self, VdfError> {
+        let Some(id) = name.strip_prefix(CLASS_GROUP_PREFIX) else {
+            return Self::by_name(name);
+        };
+        let invalid = || VdfError::UnsupportedParameters(format!("Invalid class group set {}", name));
+        let (bits, digest) = id.split_once('-').ok_or_else(invalid)?;
+        let bits = bits.parse().map_err(|_| invalid())?;
+        let digest: [u8; 32] = hex::decode(digest).ok()
+            .and_then(|digest| digest.try_into().ok())
+            .ok_or_else(invalid)?;
+        Ok(Self::class_group(bits, &digest))
+    }
+
+    /// Check that this is a published set, field for field
+    pub fn check_known(&self) -> Result<(), VdfError> {
+        if Self::by_name(&self.name)? != *self {
+            return Err(VdfError::UnsupportedParameters(format!(
+                "Parameter set {} does not match the published values",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether new outputs may be computed with this set
+    pub fn is_current(&self) -> bool {
+        *self == Self::current()
+    }
+
+    /// Whether RSA elements are taken up to sign, see `rsa_group`
+    pub fn quotient_group(&self) -> bool {
+        self.proof_version >= 2
+    }
+
+    /// Challenge context of a statement under this set, see `challenge_context`
+    pub fn challenge_context(&self, scheme: SchemeKind, action_type: &str) -> Vec<u8> {
+        challenge_context_for_version(self.proof_version, scheme, action_type)
+    }
+
+    /// Pure Rust group of a published RSA set
+    pub fn rsa_group(&self) -> Result<BigUintRsaGroup, VdfError> {
+        self.check_rsa()?;
+        let group = BigUintRsaGroup::rsa_2048();
+        Ok(if self.quotient_group() { group } else { group.plain_residues() })
+    }
+
+    /// GMP group of a published RSA set
+    #[cfg(feature = "gmp")]
+    pub fn rug_group(&self) -> Result<RugRsaGroup, VdfError> {
+        self.check_rsa()?;
+        let group = RugRsaGroup::rsa_2048();
+        Ok(if self.quotient_group() { group } else { group.plain_residues() })
+    }
+
+    fn check_rsa(&self) -> Result<(), VdfError> {
+        self.check_known()?;
+        if self.modulus_id != RSA_2048_MODULUS_ID {
+            return Err(VdfError::UnsupportedParameters(format!("{} is not an RSA group", self.modulus_id)));
+        }
+        Ok(())
+    }
+
+    /// Verify `output` with the pure Rust backend, refusing outputs made
+    /// with another set
+    ///
+    /// Like `verify_output_in`, this does not enforce an iteration limit.
+    pub fn verify_output(&self, output: &VDFOutput) -> Result<bool, VdfError> {
+        self.check_output(output)?;
+        verify_output_in(&self.rsa_group()?, output)
+    }
+
+    /// `ParameterMismatch` unless `output` was made with this set
+    pub fn check_output(&self, output: &VDFOutput) -> Result<(), VdfError> {
+        self.check_recorded(&output.parameters)
+    }
+
+    /// `ParameterMismatch` unless `recorded` is this set
+    pub fn check_recorded(&self, recorded: &ParameterSet) -> Result<(), VdfError> {
+        if *recorded != *self {
+            return Err(VdfError::ParameterMismatch {
+                expected: self.name.clone(),
+                found: recorded.name.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl From<&PublishedSet> for ParameterSet {
+    fn from(set: &PublishedSet) -> Self {
+        Self {
+            name: set.name.to_string(),
+            modulus_id: set.modulus_id.to_string(),
+            hash_to_prime_version: set.hash_to_prime_version,
+            proof_version: set.proof_version,
+            security_bits: set.security_bits,
+            iterations_per_second: set.iterations_per_second,
+        }
+    }
+}
+
+impl Default for ParameterSet {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+#[cfg(all(test, feature = "gmp"))]
+mod tests {
+    use super::*;
+    use crate::{VDFEngine, VDFInput};
+
+    fn input() -> VDFInput {
+        VDFInput {
+            player_id: 6,
+            action_type: "craft".to_string(),
+            action_id: 3,
+            timestamp: 1234567890,
+            nonce: 2,
+            random_salt: 4040,
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        assert!(ParameterSet::current().is_current());
+        assert!(!ParameterSet::legacy().is_current());
+        for set in ParameterSet::known() {
+            assert_eq!(ParameterSet::by_name(&set.name), Ok(set.clone()));
+            assert!(set.check_known().is_ok());
+        }
+        assert!(ParameterSet::by_name("rsa4096-v1").is_err());
+
+        let mut edited = ParameterSet::current();
+        edited.iterations_per_second *= 2;
+        assert!(matches!(edited.check_known(), Err(VdfError::UnsupportedParameters(_))));
+        assert!(ParameterSet::class_group(1024, &[7; 32]).check_known().is_err());
+    }
+
+    #[test]
+    fn test_class_group_names() {
+        let set = ParameterSet::class_group(1024, &[7; 32]);
+        assert_eq!(set.modulus_id, set.name);
+        assert_eq!(ParameterSet::from_name(&set.name), Ok(set.clone()));
+        assert_ne!(set, ParameterSet::class_group(1024, &[8; 32]));
+
+        for name in ["class-group-1024", "class-group-x-0707", "class-group-1024-0707"] {
+            assert!(matches!(ParameterSet::from_name(name), Err(VdfError::UnsupportedParameters(_))));
+        }
+    }
+
+    #[test]
+    fn test_sets_do_not_mix() {
+        let output = VDFEngine::new().compute(&input(), 400).expect("VDF computation failed");
+        assert_eq!(output.parameters, ParameterSet::current());
+        assert!(ParameterSet::current().verify_output(&output).expect("Verification failed"));
+        assert_eq!(
+            ParameterSet::legacy().verify_output(&output),
+            Err(VdfError::ParameterMismatch {
+                expected: LEGACY_PARAMETER_SET.to_string(),
+                found: CURRENT_PARAMETER_SET.to_string(),
+            })
+        );
+
+        // Relabelling an output changes the statement, so it no longer verifies
+        let mut relabelled = output.clone();
+        relabelled.parameters = ParameterSet::legacy();
+        assert!(!ParameterSet::legacy().verify_output(&relabelled).unwrap_or(false));
+    }
+}
@@ -0,0 +1,235 @@
+//! Pietrzak halving proofs
+//!
+//! The statement `x^(2^T) = y` is halved repeatedly: the prover sends the
+//! midpoint `mu = x^(2^(T/2))`, both halves are folded into a single
+//! statement `(x^r * mu, mu^r * y, T/2)` with a hashed challenge `r`, and the
+//! process continues until `T <= TAIL_ITERATIONS`, which the verifier squares
+//! out directly. An odd `T` is first turned into `x^(2^(T+1)) = y^2`.
+//!
+//! The proof has about `log2(T)` elements. In the first rounds the midpoints
+//! are rebuilt from the checkpoints of the squaring trace; once that gets
+//! more expensive than squaring the folded `x` directly, the prover switches
+//! over. Proving runs on GMP; verification is generic over `VdfGroup`.
+//! The folding challenge is defined in `zkgame_vdf_core::pietrzak`.
+
+use num_bigint::BigUint;
+#[cfg(feature = "gmp")]
+use rug::Integer;
+use zkgame_vdf_core::pietrzak::folding_challenge;
+#[cfg(feature = "gmp")]
+use zkgame_vdf_core::pietrzak::CHALLENGE_BITS;
+
+#[cfg(feature = "gmp")]
+use crate::checkpoint::SquaringTrace;
+use crate::error::VdfError;
+use crate::group::VdfGroup;
+#[cfg(feature = "gmp")]
+use crate::rsa_group::{canonical, to_integer, RugRsaGroup};
+#[cfg(feature = "gmp")]
+use crate::scheme::{SchemeKind, VdfScheme};
+
+pub use zkgame_vdf_core::pietrzak::TAIL_ITERATIONS;
+
+/// Pietrzak halving proof
+#[cfg(feature = "gmp")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pietrzak;
+
+#[cfg(feature = "gmp")]
+impl VdfScheme for Pietrzak {
+    fn kind(&self) -> SchemeKind {
+        SchemeKind::Pietrzak
+    }
+
+    fn prove(&self, modulus: &Integer, context: &[u8], trace: &SquaringTrace, input: &Integer, output: &Integer) -> Result<Vec<Integer>, VdfError> {
+        let group = RugRsaGroup::new(modulus.clone());
+        let interval = trace.plan().interval;
+        let mut x = input.clone();
+        let mut y = canonical(modulus, output.clone());
+        let mut t = trace.plan().iterations;
+
+        // The folded x as a product of trace values: x = prod value(position)^exponent
+        let mut terms: Option<Vec<(u64, Integer)>> = Some(vec![(0, Integer::from(1))]);
+        let mut proof = Vec::new();
+        while t > TAIL_ITERATIONS {
+            if t % 2 == 1 {
+                y = canonical(modulus, y.square());
+                t += 1;
+            }
+            let half = t / 2;
+
+            // Each term needs up to `interval` squarings plus an exponentiation
+            // by a product of the challenges so far
+            let round = proof.len() as u64;
+            terms = terms.filter(|terms| {
+                let per_term = interval.saturating_add(CHALLENGE_BITS.saturating_mul(round));
+                (terms.len() as u64).saturating_mul(per_term) < half
+            });
+            let mu = match &terms {
+                Some(terms) => midpoint_from_trace(modulus, trace, output, terms, half)?,
+                None => square_times(modulus, x.clone(), half),
+            };
+            let mu = canonical(modulus, mu);
+
+            let r = to_integer(&challenge(&group, context, &x, &y, &mu, t)?);
+            x = canonical(modulus, x.pow_mod(&r, modulus)
+                .map_err(|e| VdfError::Arithmetic(format!("Folding error: {}", e)))? * &mu);
+            y = canonical(modulus, mu.clone().pow_mod(&r, modulus)
+                .map_err(|e| VdfError::Arithmetic(format!("Folding error: {}", e)))? * y);
+            terms = terms.map(|terms| {
+                terms.into_iter()
+                    .flat_map(|(position, exponent)| {
+                        let folded = Integer::from(&exponent * &r);
+                        [(position, folded), (position + half, exponent)]
+                    })
+                    .collect()
+            });
+            t = half;
+            proof.push(mu);
+        }
+
+        Ok(proof)
+    }
+
+    fn verify(&self, modulus: &Integer, context: &[u8], input: &Integer, output: &Integer, iterations: u64, proof: &[Integer]) -> Result<bool, VdfError> {
+        verify_in_group(&RugRsaGroup::new(modulus.clone()), context, input, output, iterations, proof)
+    }
+}
+
+/// Check a halving proof for `output = input^(2^iterations)` in `group`
+///
+/// `output` and every midpoint must be canonical group elements; `input`
+/// is derived by the caller and trusted.
+pub fn verify_in_group<G: VdfGroup>(group: &G, context: &[u8], input: &G::Element, output: &G::Element, iterations: u64, proof: &[G::Element]) -> Result<bool, VdfError> {
+    if !group.is_valid(output) || !proof.iter().all(|mu| group.is_valid(mu)) {
+        return Ok(false);
+    }
+
+    let mut x = input.clone();
+    let mut y = output.clone();
+    let mut t = iterations;
+
+    let mut midpoints = proof.iter();
+    while t > TAIL_ITERATIONS {
+        let mu = match midpoints.next() {
+            Some(mu) => mu,
+            None => return Ok(false),
+        };
+        if t % 2 == 1 {
+            y = group.square(&y);
+            t += 1;
+        }
+
+        let r = challenge(group, context, &x, &y, mu, t)?;
+        x = group.mul(&group.exp(&x, &r), mu);
+        y = group.mul(&group.exp(mu, &r), &y);
+        t /= 2;
+    }
+
+    // The round count is fixed by `iterations`; extra midpoints are malformed
+    if midpoints.next().is_some() {
+        return Ok(false);
+    }
+
+    for _ in 0..t {
+        x = group.square(&x);
+    }
+    Ok(x == y)
+}
+
+/// Folding challenge `r` of the round at `mu`, see `zkgame_vdf_core::pietrzak`
+fn challenge<G: VdfGroup>(group: &G, context: &[u8], x: &G::Element, y: &G::Element, mu: &G::Element, t: u64) -> Result<BigUint, VdfError> {
+    Ok(folding_challenge(context, &group.encode(x)?, &group.encode(y)?, &group.encode(mu)?, t))
+}
+
+/// Midpoint `prod value(position + half)^exponent` rebuilt from the trace
+#[cfg(feature = "gmp")]
+fn midpoint_from_trace(modulus: &Integer, trace: &SquaringTrace, output: &Integer, terms: &[(u64, Integer)], half: u64) -> Result<Integer, VdfError> {
+    let mut mu = Integer::from(1);
+    for (position, exponent) in terms {
+        let value = trace_value(modulus, trace, output, position + half);
+        mu = (mu * value.pow_mod(exponent, modulus)
+            .map_err(|e| VdfError::Arithmetic(format!("Midpoint error: {}", e)))?) % modulus;
+    }
+    Ok(mu)
+}
+
+/// `x^(2^position)` from the nearest checkpoint at or below `position`.
+/// Odd-length rounds can reach slightly past the end of the trace, which is
+/// continued from the output.
+#[cfg(feature = "gmp")]
+fn trace_value(modulus: &Integer, trace: &SquaringTrace, output: &Integer, position: u64) -> Integer {
+    let plan = trace.plan();
+    let (start, remaining) = if position >= plan.iterations {
+        (output.clone(), position - plan.iterations)
+    } else {
+        let index = (position / plan.interval) as usize;
+        (trace.checkpoints()[index].clone(), position % plan.interval)
+    };
+    square_times(modulus, start, remaining)
+}
+
+#[cfg(feature = "gmp")]
+fn square_times(modulus: &Integer, mut value: Integer, times: u64) -> Integer {
+    for _ in 0..times {
+        value = value.square() % modulus;
+    }
+    value
+}
+
+#[cfg(all(test, feature = "gmp"))]
+mod tests {
+    use super::*;
+    use crate::checkpoint::{CheckpointConfig, CheckpointPlan};
+
+    const CONTEXT: &[u8] = b"test context";
+
+    fn small_modulus() -> Integer {
+        Integer::from(1_000_003u64) * Integer::from(999_983u64) * Integer::from(1_000_033u64)
+    }
+
+    fn trace_for(modulus: &Integer, input: &Integer, iterations: u64, interval: u64) -> (SquaringTrace, Integer) {
+        let config = CheckpointConfig {
+            interval: Some(interval),
+            ..CheckpointConfig::default()
+        };
+        let mut trace = SquaringTrace::new(CheckpointPlan::new(iterations, &config));
+        let mut value = input.clone();
+        for i in 0..iterations {
+            trace.observe(i, &value);
+            value = value.square() % modulus;
+        }
+        (trace, canonical(modulus, value))
+    }
+
+    #[test]
+    fn test_pietrzak_round_trip() {
+        let modulus = small_modulus();
+        let input = Integer::from(123_456_789u64);
+        for (iterations, interval) in [(0, 1), (64, 8), (65, 8), (777, 1), (1000, 12), (4097, 64)] {
+            let (trace, output) = trace_for(&modulus, &input, iterations, interval);
+            let proof = Pietrzak.prove(&modulus, CONTEXT, &trace, &input, &output).expect("Proof generation failed");
+            assert!(Pietrzak.verify(&modulus, CONTEXT, &input, &output, iterations, &proof).expect("Verification failed"),
+                "{} iterations with interval {} did not verify", iterations, interval);
+        }
+    }
+
+    #[test]
+    fn test_pietrzak_rejects_tampered_proof() {
+        let modulus = small_modulus();
+        let input = Integer::from(987_654_321u64);
+        let (trace, output) = trace_for(&modulus, &input, 1000, 10);
+        let proof = Pietrzak.prove(&modulus, CONTEXT, &trace, &input, &output).expect("Proof generation failed");
+
+        let mut tampered = proof.clone();
+        tampered[0] = (tampered[0].clone() * 2u32) % &modulus;
+        assert!(!Pietrzak.verify(&modulus, CONTEXT, &input, &output, 1000, &tampered).expect("Verification failed"));
+
+        let mut truncated = proof.clone();
+        truncated.pop();
+        assert!(!Pietrzak.verify(&modulus, CONTEXT, &input, &output, 1000, &truncated).expect("Verification failed"));
+
+        assert!(!Pietrzak.verify(&modulus, CONTEXT, &input, &output, 999, &proof).expect("Verification failed"));
+        assert!(!Pietrzak.verify(&modulus, b"other context", &input, &output, 1000, &proof).expect("Verification failed"));
+    }
+}
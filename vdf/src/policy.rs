@@ -0,0 +1,257 @@
+//! Freshness and anchoring rules applied on top of proof verification
+//!
+//! `VDFInput.timestamp` is chosen by the client, so a valid proof alone does
+//! not show when the delay was run. A `VerificationPolicy` additionally
+//! rejects inputs dated in the future, inputs whose delay ended too long
+//! ago, and, when an anchor is required, inputs that were not bound to a
+//! recent unpredictable public value: a block hash or a beacon value.
+//!
+//! The anchor travels in the action type, `<action>@<hex(EpochSeed)>` (see
+//! `anchored_action_type`), so it is hashed into the input seed and the
+//! challenge like the rest of the action. Nobody can start the delay before
+//! the anchor is public, which rules out pre-computing proofs for
+//! future-dated inputs. That only holds if nobody could know the anchor
+//! earlier, so epoch numbers never satisfy an anchor requirement: the
+//! number of a future epoch is known today (see `EpochSeed::is_unpredictable`).
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Serialize, Deserialize};
+
+use crate::beacon::EpochSeed;
+use crate::error::VdfError;
+use crate::group::{verify_output_in, VdfGroup};
+use crate::{VDFInput, VDFOutput};
+
+/// Separates the action from its anchor in an anchored action type
+pub const ANCHOR_SEPARATOR: char = '@';
+
+/// Default tolerance in seconds between client and verifier clocks
+pub const DEFAULT_MAX_CLOCK_SKEW: u64 = 300;
+
+/// Outcome of verifying an output under a `VerificationPolicy`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "verdict", rename_all = "snake_case")]
+pub enum Verdict {
+    /// The proof verifies and the input satisfies the policy
+    Valid,
+    /// The proof does not verify
+    InvalidProof,
+    /// The delay ended `age` seconds ago, more than the policy allows
+    Stale { age: u64, max_age: u64 },
+    /// The input is dated `ahead` seconds past the verifier's clock
+    FutureDated { ahead: u64, max_clock_skew: u64 },
+    /// The input carries no anchor or one the policy does not accept
+    WrongAnchor { found: Option<EpochSeed> },
+}
+
+impl Verdict {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Verdict::Valid)
+    }
+}
+
+/// Freshness and anchoring requirements for accepted outputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationPolicy {
+    max_age: Option<u64>,
+    max_clock_skew: u64,
+    accepted_anchors: Vec<EpochSeed>,
+}
+
+impl VerificationPolicy {
+    /// Policy with the default clock skew, no age limit and no anchor
+    pub fn new() -> Self {
+        Self {
+            max_age: None,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            accepted_anchors: Vec::new(),
+        }
+    }
+
+    /// Reject outputs whose delay ended more than `seconds` ago
+    ///
+    /// The delay is taken to end at `timestamp` plus the nominal duration of
+    /// its iterations at the rate of the output's parameter set, so one limit
+    /// fits actions of any length.
+    pub fn with_max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Accept timestamps up to `seconds` ahead of the verifier's clock
+    pub fn with_max_clock_skew(mut self, seconds: u64) -> Self {
+        self.max_clock_skew = seconds;
+        self
+    }
+
+    /// Require an anchor and accept `anchor`; call again to accept several,
+    /// e.g. the latest and the previous block hash
+    ///
+    /// Only unpredictable anchors count. An `EpochSeed::Epoch` passed here
+    /// is never matched, so a policy accepting nothing else rejects every
+    /// output with `Verdict::WrongAnchor`.
+    pub fn with_required_anchor(mut self, anchor: EpochSeed) -> Self {
+        self.accepted_anchors.push(anchor);
+        self
+    }
+
+    pub fn max_age(&self) -> Option<u64> {
+        self.max_age
+    }
+
+    pub fn max_clock_skew(&self) -> u64 {
+        self.max_clock_skew
+    }
+
+    pub fn accepted_anchors(&self) -> &[EpochSeed] {
+        &self.accepted_anchors
+    }
+
+    /// Check timestamp and anchor of `output` at the Unix time `now`,
+    /// without touching the proof
+    pub fn check(&self, output: &VDFOutput, now: u64) -> Verdict {
+        let timestamp = output.input.timestamp;
+        if timestamp > now.saturating_add(self.max_clock_skew) {
+            return Verdict::FutureDated {
+                ahead: timestamp - now,
+                max_clock_skew: self.max_clock_skew,
+            };
+        }
+
+        if let Some(max_age) = self.max_age {
+            let rate = output.parameters.iterations_per_second.max(1);
+            let finished = timestamp.saturating_add(output.iterations / rate);
+            let age = now.saturating_sub(finished);
+            if age > max_age {
+                return Verdict::Stale { age, max_age };
+            }
+        }
+
+        if !self.accepted_anchors.is_empty() {
+            let found = input_anchor(&output.input);
+            let accepted = found.as_ref().is_some_and(|anchor| anchor.is_unpredictable() && self.accepted_anchors.contains(anchor));
+            if !accepted {
+                return Verdict::WrongAnchor { found };
+            }
+        }
+
+        Verdict::Valid
+    }
+
+    /// Verify `output` in `group` at the Unix time `now`
+    ///
+    /// The policy is checked first, so stale or unanchored submissions are
+    /// turned away without paying for the proof.
+    pub fn verify_in<G: VdfGroup>(&self, group: &G, output: &VDFOutput, now: u64) -> Result<Verdict, VdfError> {
+        let verdict = self.check(output, now);
+        if !verdict.is_valid() {
+            return Ok(verdict);
+        }
+        if verify_output_in(group, output)? {
+            Ok(Verdict::Valid)
+        } else {
+            Ok(Verdict::InvalidProof)
+        }
+    }
+}
+
+impl Default for VerificationPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Action type binding `action_type` to `anchor`
+pub fn anchored_action_type(action_type: &str, anchor: &EpochSeed) -> String {
+    format!("{}{}{}", action_type, ANCHOR_SEPARATOR, hex::encode(anchor.to_bytes()))
+}
+
+/// Anchor carried by `input`, if its action type has one
+pub fn input_anchor(input: &VDFInput) -> Option<EpochSeed> {
+    let (_, anchor) = input.action_type.rsplit_once(ANCHOR_SEPARATOR)?;
+    EpochSeed::from_bytes(&hex::decode(anchor).ok()?).ok()
+}
+
+/// Current Unix time in seconds
+pub fn unix_now() -> Result<u64, VdfError> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+#[cfg(all(test, feature = "gmp"))]
+mod tests {
+    use super::*;
+    use crate::rsa_group::BigUintRsaGroup;
+    use crate::VDFEngine;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn input(timestamp: u64, anchor: Option<&EpochSeed>) -> VDFInput {
+        VDFInput {
+            player_id: 4,
+            action_type: match anchor {
+                Some(anchor) => anchored_action_type("craft", anchor),
+                None => "craft".to_string(),
+            },
+            action_id: 2,
+            timestamp,
+            nonce: 1,
+            random_salt: 777,
+        }
+    }
+
+    #[test]
+    fn test_anchor_round_trip() {
+        for anchor in [EpochSeed::Epoch(42), EpochSeed::BlockHash(vec![0xcd; 32])] {
+            assert_eq!(input_anchor(&input(NOW, Some(&anchor))), Some(anchor));
+        }
+        assert_eq!(input_anchor(&input(NOW, None)), None);
+    }
+
+    #[test]
+    fn test_policy_verdicts() {
+        let engine = VDFEngine::new();
+        let group = BigUintRsaGroup::rsa_2048();
+        let block = EpochSeed::BlockHash(vec![0x12; 32]);
+        let policy = VerificationPolicy::new()
+            .with_max_age(600)
+            .with_max_clock_skew(60)
+            .with_required_anchor(block.clone());
+
+        let fresh = engine.compute(&input(NOW, Some(&block)), 500).expect("VDF computation failed");
+        assert_eq!(policy.verify_in(&group, &fresh, NOW + 30).expect("Verification failed"), Verdict::Valid);
+        assert_eq!(
+            policy.verify_in(&group, &fresh, NOW - 120).expect("Verification failed"),
+            Verdict::FutureDated { ahead: 120, max_clock_skew: 60 }
+        );
+        assert_eq!(
+            policy.verify_in(&group, &fresh, NOW + 1000).expect("Verification failed"),
+            Verdict::Stale { age: 999, max_age: 600 }
+        );
+
+        let unanchored = engine.compute(&input(NOW, None), 500).expect("VDF computation failed");
+        assert_eq!(policy.verify_in(&group, &unanchored, NOW).expect("Verification failed"), Verdict::WrongAnchor { found: None });
+        let old_block = EpochSeed::BlockHash(vec![0x11; 32]);
+        let previous = engine.compute(&input(NOW, Some(&old_block)), 500).expect("VDF computation failed");
+        assert_eq!(
+            policy.verify_in(&group, &previous, NOW).expect("Verification failed"),
+            Verdict::WrongAnchor { found: Some(old_block.clone()) }
+        );
+        let policy = policy.with_required_anchor(old_block);
+        assert!(policy.verify_in(&group, &previous, NOW).expect("Verification failed").is_valid());
+
+        // Epoch numbers are known in advance and never count as an anchor
+        let epoch = EpochSeed::Epoch(12);
+        let precomputed = engine.compute(&input(NOW, Some(&epoch)), 500).expect("VDF computation failed");
+        let policy = policy.with_required_anchor(epoch.clone());
+        assert_eq!(
+            policy.verify_in(&group, &precomputed, NOW).expect("Verification failed"),
+            Verdict::WrongAnchor { found: Some(epoch) }
+        );
+
+        // Moving the timestamp into the window breaks the proof
+        let mut redated = fresh.clone();
+        redated.input.timestamp = NOW + 1;
+        assert_eq!(policy.verify_in(&group, &redated, NOW).expect("Verification failed"), Verdict::InvalidProof);
+    }
+}
@@ -0,0 +1,104 @@
+//! Progress reporting, cancellation and resumable state for long computations
+//!
+//! `VDFEngine::compute_with_progress` reports a `Progress` snapshot every
+//! `PROGRESS_STEP` squarings and checks a `CancellationToken` before each
+//! one. A cancelled computation returns a `PartialComputation` holding the
+//! current value and the checkpoints recorded so far; `VDFEngine::resume`
+//! continues from there, and the finished proof is identical to one from an
+//! uninterrupted run.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rug::Integer;
+
+use crate::checkpoint::SquaringTrace;
+use crate::{VDFInput, VDFOutput};
+
+/// Squarings between two progress reports
+pub const PROGRESS_STEP: u64 = 1000;
+
+/// Shared flag that asks a running computation to stop
+///
+/// Clones share the same flag, so one can be moved into a Ctrl-C handler
+/// while the other is passed to the computation.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every computation holding this token to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Snapshot passed to the progress callback
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// Squarings done so far, including earlier sessions of a resumed computation
+    pub iterations_done: u64,
+    pub total_iterations: u64,
+    /// Squarings per second measured in the current session
+    pub rate: f64,
+    /// Estimated seconds until the squaring finishes at the current rate
+    pub eta_seconds: f64,
+}
+
+impl Progress {
+    /// Completed fraction in `[0, 1]`
+    pub fn fraction(&self) -> f64 {
+        if self.total_iterations == 0 {
+            return 1.0;
+        }
+        self.iterations_done as f64 / self.total_iterations as f64
+    }
+}
+
+/// State of an interrupted computation, enough to resume it later
+#[derive(Debug, Clone)]
+pub struct PartialComputation {
+    pub(crate) input: VDFInput,
+    pub(crate) input_seed: Integer,
+    pub(crate) completed: u64,
+    pub(crate) current: Integer,
+    pub(crate) trace: SquaringTrace,
+    pub(crate) elapsed: f64,
+}
+
+impl PartialComputation {
+    pub fn input(&self) -> &VDFInput {
+        &self.input
+    }
+
+    /// Total squarings requested
+    pub fn iterations(&self) -> u64 {
+        self.trace.plan().iterations
+    }
+
+    /// Squarings already done
+    pub fn completed(&self) -> u64 {
+        self.completed
+    }
+
+    /// Seconds spent squaring across all sessions so far
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+}
+
+/// Result of a computation that may have been cancelled
+#[derive(Debug, Clone)]
+pub enum ComputeStatus {
+    Complete(VDFOutput),
+    Cancelled(PartialComputation),
+}
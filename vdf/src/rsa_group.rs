@@ -0,0 +1,327 @@
+//! RSA groups for the `VdfGroup` trait
+//!
+//! Both backends work in the quotient group `(Z/NZ)* / {±1}`. In `(Z/NZ)*`
+//! itself, `(N - y, N - pi)` verifies whenever `(y, pi)` does (the challenge
+//! `l` is odd), and `-1` is a known element of small order that slips through
+//! batch checks. Taking every value up to sign removes both: an element is
+//! stored as its canonical representative `min(x, N - x)`, and the group
+//! operations return canonical representatives.
+//!
+//! Elements use the 256-byte big-endian encoding from `encoding`. Valid
+//! outputs and proofs are the values `1 < x <= (N - 1) / 2`: zero is not a
+//! unit and satisfies the Wesolowski equation for any statement with a zero
+//! proof, and `1` is the class of `±1`, the identity of the quotient group.
+//!
+//! Parameter set `rsa2048-v1` predates the quotient group. `plain_residues`
+//! returns a group with its rules, plain residues `0 < x < N`, for verifying
+//! outputs recorded under that set.
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+#[cfg(feature = "gmp")]
+use rug::Integer;
+#[cfg(feature = "gmp")]
+use rug::integer::Order;
+
+#[cfg(feature = "gmp")]
+use crate::encoding::{element_from_bytes, element_to_bytes};
+use crate::encoding::{pad_element, ELEMENT_BYTES};
+use crate::error::VdfError;
+use crate::group::VdfGroup;
+use zkgame_vdf_core::RSA_2048_MODULUS;
+
+/// RSA group on `num-bigint`, available without GMP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigUintRsaGroup {
+    modulus: BigUint,
+    quotient: bool,
+}
+
+impl BigUintRsaGroup {
+    pub fn new(modulus: BigUint) -> Self {
+        Self { modulus, quotient: true }
+    }
+
+    /// Work in `(Z/NZ)*` with plain residues, as parameter set `rsa2048-v1` did
+    pub fn plain_residues(mut self) -> Self {
+        self.quotient = false;
+        self
+    }
+
+    /// The RSA-2048 group used by `VDFEngine`
+    pub fn rsa_2048() -> Self {
+        Self::new(BigUint::parse_bytes(RSA_2048_MODULUS.as_bytes(), 10)
+            .expect("Failed to parse RSA-2048 modulus"))
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Canonical representative `min(x, N - x)` of the class of `x`, or
+    /// `x mod N` with plain residues
+    pub fn canonical(&self, element: &BigUint) -> BigUint {
+        let reduced = element % &self.modulus;
+        if !self.quotient {
+            return reduced;
+        }
+        let negated = &self.modulus - &reduced;
+        if negated < reduced { negated } else { reduced }
+    }
+}
+
+impl VdfGroup for BigUintRsaGroup {
+    type Element = BigUint;
+
+    fn description(&self) -> Vec<u8> {
+        self.encode(&self.modulus).expect("Modulus fits the element width")
+    }
+
+    fn identity(&self) -> BigUint {
+        BigUint::from(1u32)
+    }
+
+    fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.canonical(&(a * b))
+    }
+
+    fn exp(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        self.canonical(&base.modpow(exponent, &self.modulus))
+    }
+
+    fn element_from_seed(&self, seed: &[u8; 32]) -> Result<BigUint, VdfError> {
+        let element = BigUint::from_bytes_le(seed);
+        if element >= self.modulus {
+            return Err(VdfError::InvalidSeed);
+        }
+        let element = self.canonical(&element);
+        if !self.is_valid(&element) {
+            return Err(VdfError::InvalidSeed);
+        }
+        Ok(element)
+    }
+
+    fn element_bytes(&self) -> usize {
+        ELEMENT_BYTES
+    }
+
+    fn encode(&self, element: &BigUint) -> Result<Vec<u8>, VdfError> {
+        Ok(pad_element(&element.to_bytes_be())?.to_vec())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<BigUint, VdfError> {
+        if bytes.len() != ELEMENT_BYTES {
+            return Err(VdfError::MalformedProof(format!(
+                "Group element must be {} bytes, got {}",
+                ELEMENT_BYTES,
+                bytes.len()
+            )));
+        }
+        Ok(BigUint::from_bytes_be(bytes))
+    }
+
+    fn is_valid(&self, element: &BigUint) -> bool {
+        if !self.quotient {
+            return !element.is_zero() && *element < self.modulus;
+        }
+        *element > BigUint::one() && *element <= &self.modulus >> 1
+    }
+}
+
+/// RSA group on GMP, the backend `VDFEngine` squares in
+#[cfg(feature = "gmp")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RugRsaGroup {
+    modulus: Integer,
+    quotient: bool,
+}
+
+#[cfg(feature = "gmp")]
+impl RugRsaGroup {
+    pub fn new(modulus: Integer) -> Self {
+        Self { modulus, quotient: true }
+    }
+
+    /// Work in `(Z/NZ)*` with plain residues, as parameter set `rsa2048-v1` did
+    pub fn plain_residues(mut self) -> Self {
+        self.quotient = false;
+        self
+    }
+
+    /// The RSA-2048 group used by `VDFEngine`
+    pub fn rsa_2048() -> Self {
+        Self::new(Integer::from_str_radix(RSA_2048_MODULUS, 10)
+            .expect("Failed to parse RSA-2048 modulus"))
+    }
+
+    pub fn modulus(&self) -> &Integer {
+        &self.modulus
+    }
+
+    /// Canonical representative `min(x, N - x)` of the class of `x`, or
+    /// `x mod N` with plain residues
+    pub fn canonical(&self, element: &Integer) -> Integer {
+        self.reduce(element.clone())
+    }
+
+    fn reduce(&self, element: Integer) -> Integer {
+        if self.quotient {
+            canonical(&self.modulus, element)
+        } else {
+            element.div_rem_euc(self.modulus.clone()).1
+        }
+    }
+}
+
+#[cfg(feature = "gmp")]
+impl VdfGroup for RugRsaGroup {
+    type Element = Integer;
+
+    fn description(&self) -> Vec<u8> {
+        self.encode(&self.modulus).expect("Modulus fits the element width")
+    }
+
+    fn identity(&self) -> Integer {
+        Integer::from(1)
+    }
+
+    fn mul(&self, a: &Integer, b: &Integer) -> Integer {
+        self.reduce(Integer::from(a * b))
+    }
+
+    fn square(&self, element: &Integer) -> Integer {
+        self.reduce(element.clone().square())
+    }
+
+    fn exp(&self, base: &Integer, exponent: &BigUint) -> Integer {
+        self.reduce(base.clone().pow_mod(&to_integer(exponent), &self.modulus)
+            .expect("Exponent is non-negative"))
+    }
+
+    fn element_from_seed(&self, seed: &[u8; 32]) -> Result<Integer, VdfError> {
+        let element = Integer::from_digits(seed, Order::Lsf);
+        if element >= self.modulus {
+            return Err(VdfError::InvalidSeed);
+        }
+        let element = self.reduce(element);
+        if !self.is_valid(&element) {
+            return Err(VdfError::InvalidSeed);
+        }
+        Ok(element)
+    }
+
+    fn element_bytes(&self) -> usize {
+        ELEMENT_BYTES
+    }
+
+    fn encode(&self, element: &Integer) -> Result<Vec<u8>, VdfError> {
+        Ok(element_to_bytes(element)?.to_vec())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Integer, VdfError> {
+        element_from_bytes(bytes)
+    }
+
+    fn is_valid(&self, element: &Integer) -> bool {
+        if !self.quotient {
+            return *element > 0 && *element < self.modulus;
+        }
+        *element > 1 && *element <= Integer::from(&self.modulus >> 1)
+    }
+}
+
+/// Canonical representative `min(x, N - x)` of `x mod N`, for provers that
+/// square with plain GMP arithmetic
+#[cfg(feature = "gmp")]
+pub(crate) fn canonical(modulus: &Integer, element: Integer) -> Integer {
+    let (_, reduced) = element.div_rem_euc(modulus.clone());
+    let negated = Integer::from(modulus - &reduced);
+    if negated < reduced { negated } else { reduced }
+}
+
+/// Convert a `num-bigint` value, such as a challenge prime, for GMP arithmetic
+#[cfg(feature = "gmp")]
+pub(crate) fn to_integer(value: &BigUint) -> Integer {
+    Integer::from_digits(&value.to_bytes_be(), Order::Msf)
+}
+
+#[cfg(all(test, feature = "gmp"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backends_agree() {
+        let gmp = RugRsaGroup::rsa_2048();
+        let pure = BigUintRsaGroup::rsa_2048();
+        assert_eq!(gmp.description(), pure.description());
+
+        let seed = [7u8; 32];
+        let a = gmp.element_from_seed(&seed).expect("Seed out of range");
+        let b = pure.element_from_seed(&seed).expect("Seed out of range");
+        assert_eq!(gmp.encode(&a).expect("Encoding failed"), pure.encode(&b).expect("Encoding failed"));
+
+        let exponent = BigUint::parse_bytes(b"123456789abcdef0123456789abcdef", 16).expect("Invalid exponent");
+        let a = gmp.mul(&gmp.exp(&a, &exponent), &gmp.square(&a));
+        let b = pure.mul(&pure.exp(&b, &exponent), &pure.square(&b));
+        let encoded = pure.encode(&b).expect("Encoding failed");
+        assert_eq!(gmp.encode(&a).expect("Encoding failed"), encoded);
+        assert_eq!(gmp.decode(&encoded).expect("Decoding failed"), a);
+    }
+
+    #[test]
+    fn test_element_validation() {
+        let group = BigUintRsaGroup::rsa_2048();
+        let half = group.modulus() >> 1;
+        assert!(group.is_valid(&BigUint::from(2u32)));
+        assert!(group.is_valid(&half));
+        assert!(!group.is_valid(&BigUint::from(0u32)));
+        assert!(!group.is_valid(&BigUint::one()));
+        assert!(!group.is_valid(&(&half + 1u32)));
+        assert!(!group.is_valid(&(group.modulus() - 1u32)));
+        assert!(!group.is_valid(group.modulus()));
+        assert!(group.decode(&[0u8; ELEMENT_BYTES - 1]).is_err());
+
+        let gmp = RugRsaGroup::rsa_2048();
+        assert!(!gmp.is_valid(&Integer::new()));
+        assert!(!gmp.is_valid(&Integer::from(1)));
+        assert!(!gmp.is_valid(&Integer::from(-2)));
+        assert!(!gmp.is_valid(&Integer::from(gmp.modulus() - 1u32)));
+        assert!(!gmp.is_valid(&Integer::from(gmp.modulus() + 1u32)));
+    }
+
+    #[test]
+    fn test_operations_return_canonical_representatives() {
+        let gmp = RugRsaGroup::rsa_2048();
+        let pure = BigUintRsaGroup::rsa_2048();
+        let x = pure.element_from_seed(&[9; 32]).expect("Seed out of range");
+        let negated = pure.modulus() - &x;
+        assert_eq!(pure.canonical(&negated), x);
+        assert_eq!(pure.mul(&negated, &x), pure.square(&x));
+        assert!(pure.is_valid(&pure.square(&x)));
+
+        let y = gmp.element_from_seed(&[9; 32]).expect("Seed out of range");
+        assert_eq!(gmp.canonical(&Integer::from(-&y)), y);
+        assert_eq!(gmp.encode(&gmp.square(&y)).expect("Encoding failed"), pure.encode(&pure.square(&x)).expect("Encoding failed"));
+        assert!(gmp.element_from_seed(&[0; 32]).is_err());
+        assert!(pure.element_from_seed(&[0; 32]).is_err());
+    }
+
+    #[test]
+    fn test_plain_residues() {
+        let gmp = RugRsaGroup::rsa_2048().plain_residues();
+        let pure = BigUintRsaGroup::rsa_2048().plain_residues();
+        let minus_two = pure.modulus() - 2u32;
+        assert!(pure.is_valid(&BigUint::one()));
+        assert!(pure.is_valid(&minus_two));
+        assert!(!pure.is_valid(&BigUint::zero()));
+        assert!(!pure.is_valid(pure.modulus()));
+        assert_eq!(pure.canonical(&minus_two), minus_two);
+        assert_eq!(pure.square(&minus_two), BigUint::from(4u32));
+
+        let minus_two = Integer::from(gmp.modulus() - 2u32);
+        assert!(gmp.is_valid(&minus_two));
+        assert_eq!(gmp.canonical(&Integer::from(-2)), minus_two);
+        assert_eq!(gmp.encode(&gmp.mul(&minus_two, &Integer::from(3))).expect("Encoding failed"),
+            pure.encode(&(pure.modulus() - 6u32)).expect("Encoding failed"));
+    }
+}
@@ -0,0 +1,110 @@
+//! Proof schemes for the squaring chain `output = input^(2^T)`
+//!
+//! Both schemes share the RSA-2048 group, the input seed derivation and the
+//! checkpointed squaring trace; only the proof attached to a `VDFOutput`
+//! differs.
+
+#[cfg(feature = "gmp")]
+use rug::Integer;
+use serde::{Serialize, Deserialize};
+use zkgame_vdf_core::statement::Scheme;
+
+#[cfg(feature = "gmp")]
+use crate::checkpoint::SquaringTrace;
+use crate::error::VdfError;
+#[cfg(feature = "gmp")]
+use crate::pietrzak::Pietrzak;
+#[cfg(feature = "gmp")]
+use crate::wesolowski::Wesolowski;
+
+/// Identifies which proof scheme produced a `VDFOutput`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SchemeKind {
+    /// Single-element proof, cheapest to verify
+    #[default]
+    Wesolowski,
+    /// `log2(T)`-element halving proof, cheapest to generate
+    Pietrzak,
+}
+
+impl SchemeKind {
+    /// Byte identifying the scheme in binary encodings and challenges
+    pub fn tag(&self) -> u8 {
+        Scheme::from(*self).tag()
+    }
+
+    /// Inverse of `tag`
+    pub fn from_tag(tag: u8) -> Result<Self, VdfError> {
+        Ok(Scheme::from_tag(tag)?.into())
+    }
+
+    /// Scheme implementation for this kind
+    #[cfg(feature = "gmp")]
+    pub fn scheme(&self) -> &'static dyn VdfScheme {
+        match self {
+            SchemeKind::Wesolowski => &Wesolowski,
+            SchemeKind::Pietrzak => &Pietrzak,
+        }
+    }
+}
+
+impl From<SchemeKind> for Scheme {
+    fn from(kind: SchemeKind) -> Self {
+        match kind {
+            SchemeKind::Wesolowski => Scheme::Wesolowski,
+            SchemeKind::Pietrzak => Scheme::Pietrzak,
+        }
+    }
+}
+
+impl From<Scheme> for SchemeKind {
+    fn from(scheme: Scheme) -> Self {
+        match scheme {
+            Scheme::Wesolowski => SchemeKind::Wesolowski,
+            Scheme::Pietrzak => SchemeKind::Pietrzak,
+        }
+    }
+}
+
+/// A proof of correct exponentiation for `output = input^(2^iterations)`
+///
+/// `context` is hashed into every Fiat-Shamir challenge together with the
+/// modulus, `iterations`, `input` and `output`, so a proof only verifies for
+/// the statement it was made for. `VDFEngine` passes `challenge_context`.
+#[cfg(feature = "gmp")]
+pub trait VdfScheme: Send + Sync {
+    /// Kind recorded in the `VDFOutput`
+    fn kind(&self) -> SchemeKind;
+
+    /// Build a proof from the trace recorded while squaring `input` into `output`
+    ///
+    /// `output` may be either sign; the statement and the returned proof
+    /// elements use canonical representatives, see `rsa_group`.
+    fn prove(&self, modulus: &Integer, context: &[u8], trace: &SquaringTrace, input: &Integer, output: &Integer) -> Result<Vec<Integer>, VdfError>;
+
+    /// `prove` using up to `threads` worker threads; schemes without a
+    /// parallel prover fall back to `prove`
+    fn prove_parallel(&self, modulus: &Integer, context: &[u8], trace: &SquaringTrace, input: &Integer, output: &Integer, _threads: usize) -> Result<Vec<Integer>, VdfError> {
+        self.prove(modulus, context, trace, input, output)
+    }
+
+    /// Check `proof` for `output = input^(2^iterations)` in `(Z/NZ)* / {±1}`;
+    /// `output` and the proof elements must be canonical
+    fn verify(&self, modulus: &Integer, context: &[u8], input: &Integer, output: &Integer, iterations: u64, proof: &[Integer]) -> Result<bool, VdfError>;
+}
+
+pub use zkgame_vdf_core::statement::PROOF_VERSION;
+
+/// Game context bound into the challenge of a proof:
+/// `PROOF_VERSION u8 | scheme tag u8 | u32 length | action_type`, see
+/// `zkgame_vdf_core::challenge_context`.
+/// The remaining `VDFInput` fields enter through the input seed.
+pub fn challenge_context(scheme: SchemeKind, action_type: &str) -> Vec<u8> {
+    challenge_context_for_version(PROOF_VERSION, scheme, action_type)
+}
+
+/// `challenge_context` for the statement version of an older parameter set
+pub(crate) fn challenge_context_for_version(version: u8, scheme: SchemeKind, action_type: &str) -> Vec<u8> {
+    zkgame_vdf_core::challenge_context(version, scheme.into(), action_type)
+}
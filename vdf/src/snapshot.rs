@@ -0,0 +1,201 @@
+//! On-disk snapshots of interrupted computations
+//!
+//! A snapshot stores everything `VDFEngine::resume` needs: the input, the
+//! checkpoint plan, the iteration index, the current value and the
+//! checkpoints recorded so far. All integers are big-endian and group
+//! elements use the fixed-width encoding from `encoding`:
+//!
+//! `magic "ZKVDFSNP" | version u8 | input | iterations u64 | window_bits u8 |
+//! stride u64 | interval u64 | completed u64 | elapsed f64 | current |
+//! checkpoint count u32 | checkpoints | SHA-256 of everything before`
+//!
+//! The trailing digest is an unkeyed SHA-256: it catches truncated or
+//! corrupted files, but anyone who edits a snapshot can recompute it.
+//! `VDFEngine::load_snapshot` checks what it can afford against the squaring
+//! chain: the first checkpoint is the seed and the current value follows
+//! from the last checkpoint. Checkpoints in between cannot be checked
+//! without redoing the squarings they stand for, so a snapshot whose earlier
+//! checkpoints were altered loads and resumes normally. The damage only
+//! shows when `VDFEngine::compute_persistent` verifies the finished proof,
+//! which fails after all remaining squarings have been done; for a long
+//! action that is hours of wasted work. The snapshot is then removed and
+//! the computation has to start over.
+//!
+//! No forged snapshot yields an accepted proof, so this is a cost to
+//! whoever tampered with it rather than a weakness of the proofs.
+
+use std::fs;
+use std::path::Path;
+
+use sha2::{Sha256, Digest};
+use zkgame_vdf_core::encoding::Reader;
+
+use crate::checkpoint::{CheckpointPlan, SquaringTrace, MAX_WINDOW_BITS};
+use crate::encoding::{element_from_bytes, element_to_bytes, ELEMENT_BYTES};
+use crate::error::VdfError;
+use crate::{input_seed, VDFInput};
+use crate::progress::PartialComputation;
+
+/// File signature of a snapshot
+const SNAPSHOT_MAGIC: &[u8; 8] = b"ZKVDFSNP";
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+/// Size of the trailing digest
+const DIGEST_BYTES: usize = 32;
+
+impl PartialComputation {
+    /// Serialize into the versioned snapshot format
+    pub fn to_snapshot_bytes(&self) -> Result<Vec<u8>, VdfError> {
+        let plan = self.trace.plan();
+        let checkpoints = self.trace.checkpoints();
+        let mut bytes = Vec::with_capacity(128 + (checkpoints.len() + 1) * ELEMENT_BYTES);
+        bytes.extend_from_slice(SNAPSHOT_MAGIC);
+        bytes.push(SNAPSHOT_VERSION);
+        self.input.write_to(&mut bytes);
+        bytes.extend_from_slice(&plan.iterations.to_be_bytes());
+        bytes.push(plan.window_bits as u8);
+        bytes.extend_from_slice(&plan.stride.to_be_bytes());
+        bytes.extend_from_slice(&plan.interval.to_be_bytes());
+        bytes.extend_from_slice(&self.completed.to_be_bytes());
+        bytes.extend_from_slice(&self.elapsed.to_bits().to_be_bytes());
+        bytes.extend_from_slice(&element_to_bytes(&self.current)?);
+        bytes.extend_from_slice(&(checkpoints.len() as u32).to_be_bytes());
+        for checkpoint in checkpoints {
+            bytes.extend_from_slice(&element_to_bytes(checkpoint)?);
+        }
+
+        let digest = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&digest);
+        Ok(bytes)
+    }
+
+    /// Parse a snapshot written by `to_snapshot_bytes`, checking its digest and layout
+    pub fn from_snapshot_bytes(bytes: &[u8]) -> Result<Self, VdfError> {
+        if bytes.len() < SNAPSHOT_MAGIC.len() + 1 + DIGEST_BYTES || &bytes[..SNAPSHOT_MAGIC.len()] != SNAPSHOT_MAGIC {
+            return Err(VdfError::InvalidSnapshot("Not a VDF snapshot".to_string()));
+        }
+        let version = bytes[SNAPSHOT_MAGIC.len()];
+        if version != SNAPSHOT_VERSION {
+            return Err(VdfError::InvalidSnapshot(format!("Unsupported snapshot version {}", version)));
+        }
+        let (body, digest) = bytes.split_at(bytes.len() - DIGEST_BYTES);
+        if Sha256::digest(body)[..] != *digest {
+            return Err(VdfError::InvalidSnapshot("Digest mismatch".to_string()));
+        }
+
+        fn malformed(e: impl std::fmt::Display) -> VdfError {
+            VdfError::InvalidSnapshot(e.to_string())
+        }
+        let mut reader = Reader::new(&body[SNAPSHOT_MAGIC.len() + 1..]);
+        let input = VDFInput::read_from(&mut reader).map_err(malformed)?;
+        let iterations = reader.u64().map_err(malformed)?;
+        let window_bits = reader.take(1).map_err(malformed)?[0] as u32;
+        let stride = reader.u64().map_err(malformed)?;
+        let interval = reader.u64().map_err(malformed)?;
+        let completed = reader.u64().map_err(malformed)?;
+        let elapsed = f64::from_bits(reader.u64().map_err(malformed)?);
+        let current = element_from_bytes(reader.take(ELEMENT_BYTES).map_err(malformed)?).map_err(malformed)?;
+        let count = reader.u32().map_err(malformed)?;
+        let checkpoints = (0..count)
+            .map(|_| reader.take(ELEMENT_BYTES).map_err(malformed).and_then(|bytes| element_from_bytes(bytes).map_err(malformed)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if !reader.is_empty() {
+            return Err(VdfError::InvalidSnapshot("Trailing bytes after checkpoints".to_string()));
+        }
+
+        if !(1..=MAX_WINDOW_BITS).contains(&window_bits) || stride == 0 || interval != window_bits as u64 * stride {
+            return Err(VdfError::InvalidSnapshot("Inconsistent checkpoint plan".to_string()));
+        }
+        if completed > iterations || checkpoints.len() as u64 != completed.div_ceil(interval) {
+            return Err(VdfError::InvalidSnapshot("Checkpoint count does not match progress".to_string()));
+        }
+        if !elapsed.is_finite() || elapsed < 0.0 {
+            return Err(VdfError::InvalidSnapshot("Invalid elapsed time".to_string()));
+        }
+
+        let plan = CheckpointPlan {
+            iterations,
+            window_bits,
+            stride,
+            interval,
+        };
+        Ok(PartialComputation {
+            input_seed: input_seed(&input),
+            input,
+            completed,
+            current,
+            trace: SquaringTrace::from_parts(plan, checkpoints),
+            elapsed,
+        })
+    }
+
+    /// Write the snapshot to `path`, replacing any previous one atomically
+    pub fn save(&self, path: &Path) -> Result<(), VdfError> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, self.to_snapshot_bytes()?)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Read a snapshot written by `save`
+    pub fn load(path: &Path) -> Result<Self, VdfError> {
+        Self::from_snapshot_bytes(&fs::read(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CancellationToken, CheckpointConfig, ComputeStatus, VDFEngine, VDFInput, PROGRESS_STEP};
+
+    fn partial() -> PartialComputation {
+        let input = VDFInput {
+            player_id: 1,
+            action_type: "craft".to_string(),
+            action_id: 1,
+            timestamp: 1234567890,
+            nonce: 1,
+            random_salt: 12345,
+        };
+        let engine = VDFEngine::new().with_checkpoint_config(CheckpointConfig {
+            interval: Some(10),
+            ..CheckpointConfig::default()
+        });
+
+        // Stop at the first progress report
+        let cancel = CancellationToken::new();
+        match engine.compute_with_progress(&input, 2500, |_| cancel.cancel(), &cancel).expect("VDF computation failed") {
+            ComputeStatus::Cancelled(partial) => partial,
+            ComputeStatus::Complete(_) => panic!("Computation should have been cancelled"),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let partial = partial();
+        let bytes = partial.to_snapshot_bytes().expect("Encoding failed");
+        let decoded = PartialComputation::from_snapshot_bytes(&bytes).expect("Decoding failed");
+        assert_eq!(decoded.to_snapshot_bytes().expect("Encoding failed"), bytes);
+        assert_eq!(decoded.iterations(), 2500);
+        assert_eq!(decoded.completed(), PROGRESS_STEP);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_corruption() {
+        let bytes = partial().to_snapshot_bytes().expect("Encoding failed");
+
+        let mut flipped = bytes.clone();
+        let middle = flipped.len() / 2;
+        flipped[middle] ^= 1;
+        assert!(matches!(PartialComputation::from_snapshot_bytes(&flipped), Err(VdfError::InvalidSnapshot(_))));
+
+        assert!(PartialComputation::from_snapshot_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut future = bytes.clone();
+        future[SNAPSHOT_MAGIC.len()] = SNAPSHOT_VERSION + 1;
+        assert!(PartialComputation::from_snapshot_bytes(&future).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+//! Solidity verifier for Wesolowski outputs
+//!
+//! `verifier_source` renders `contracts/VDFVerifier.sol` from
+//! `templates/VDFVerifier.sol`, filling in the modulus and its half, the hash-to-prime tag
+//! and Miller-Rabin bases and the challenge context constants, so the contract
+//! always checks the same statement as `wesolowski::verify_in_group`. The
+//! checked-in contract is compared against the generator in the tests;
+//! regenerate it with
+//!
+//! ```text
+//! cargo run --example solidity_verifier > ../contracts/VDFVerifier.sol
+//! ```
+//!
+//! `verify_calldata` ABI-encodes a `VDFOutput` as a call to
+//! `VDFVerifier.verify`. Pietrzak proofs are `log2(T)` elements long and are
+//! not supported on-chain. The contract checks the current parameter set
+//! only; outputs of older sets are verified off-chain.
+
+use sha3::{Digest, Keccak256};
+
+use crate::encoding::ELEMENT_BYTES;
+use crate::error::VdfError;
+use crate::group::VdfGroup;
+use crate::hash_to_prime::{HASH_TO_PRIME_TAG, HASH_TO_PRIME_VERSION, MAX_ATTEMPTS, MILLER_RABIN_BASES};
+use crate::params::ParameterSet;
+use crate::rsa_group::BigUintRsaGroup;
+use crate::scheme::{SchemeKind, PROOF_VERSION};
+use crate::VDFOutput;
+
+/// Name of the generated contract
+pub const VERIFIER_CONTRACT: &str = "VDFVerifier";
+
+/// ABI signature of `VDFVerifier.verify`
+pub const VERIFY_SIGNATURE: &str = "verify((uint64,string,uint64,uint64,uint64,uint64),uint64,bytes,bytes)";
+
+const TEMPLATE: &str = include_str!("../templates/VDFVerifier.sol");
+
+/// Hex digits per line of the embedded modulus
+const MODULUS_LINE_DIGITS: usize = 64;
+
+/// Size of one ABI word
+const WORD_BYTES: usize = 32;
+
+/// Solidity source of a verifier for Wesolowski outputs in `group`
+pub fn verifier_source(group: &BigUintRsaGroup) -> String {
+    let half_modulus = group.encode(&(group.modulus() >> 1)).expect("Half the modulus fits the element width");
+    let bases = MILLER_RABIN_BASES
+        .iter()
+        .enumerate()
+        .map(|(index, base)| if index == 0 { format!("uint256({})", base) } else { base.to_string() })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    TEMPLATE
+        .replace("__HASH_TO_PRIME_VERSION__", &HASH_TO_PRIME_VERSION.to_string())
+        .replace("__ELEMENT_BYTES__", &group.element_bytes().to_string())
+        .replace("__PROOF_VERSION__", &PROOF_VERSION.to_string())
+        .replace("__WESOLOWSKI_TAG__", &SchemeKind::Wesolowski.tag().to_string())
+        .replace("__HASH_TO_PRIME_TAG__", &String::from_utf8_lossy(HASH_TO_PRIME_TAG))
+        .replace("__MAX_ATTEMPTS__", &MAX_ATTEMPTS.to_string())
+        .replace("__HALF_MODULUS__", &hex_lines(&half_modulus))
+        .replace("__MODULUS__", &hex_lines(&group.description()))
+        .replace("__BASE_COUNT__", &MILLER_RABIN_BASES.len().to_string())
+        .replace("__MILLER_RABIN_BASES__", &bases)
+}
+
+/// The 4-byte selector of `VERIFY_SIGNATURE`
+pub fn verify_selector() -> [u8; 4] {
+    let hash = Keccak256::digest(VERIFY_SIGNATURE.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// ABI-encoded call to `VDFVerifier.verify` for a Wesolowski output
+pub fn verify_calldata(output: &VDFOutput) -> Result<Vec<u8>, VdfError> {
+    if output.scheme != SchemeKind::Wesolowski {
+        return Err(VdfError::MalformedProof("Only Wesolowski outputs can be verified on-chain".to_string()));
+    }
+    ParameterSet::current().check_output(output)?;
+    if output.output.len() != ELEMENT_BYTES || output.proof.len() != ELEMENT_BYTES {
+        return Err(VdfError::MalformedProof(format!(
+            "Output and proof must be one {}-byte element each",
+            ELEMENT_BYTES
+        )));
+    }
+
+    // (playerId, actionType, actionId, timestamp, nonce, randomSalt), with the
+    // string offset relative to the start of the tuple
+    let input = &output.input;
+    let mut tuple = Vec::new();
+    tuple.extend_from_slice(&abi_word(input.player_id));
+    tuple.extend_from_slice(&abi_word(6 * WORD_BYTES as u64));
+    for value in [input.action_id, input.timestamp, input.nonce, input.random_salt] {
+        tuple.extend_from_slice(&abi_word(value));
+    }
+    tuple.extend_from_slice(&abi_bytes(input.action_type.as_bytes()));
+
+    let output_bytes = abi_bytes(&output.output);
+    let proof_bytes = abi_bytes(&output.proof);
+    let head = 4 * WORD_BYTES;
+
+    let mut calldata = verify_selector().to_vec();
+    calldata.extend_from_slice(&abi_word(head as u64));
+    calldata.extend_from_slice(&abi_word(output.iterations));
+    calldata.extend_from_slice(&abi_word((head + tuple.len()) as u64));
+    calldata.extend_from_slice(&abi_word((head + tuple.len() + output_bytes.len()) as u64));
+    calldata.extend_from_slice(&tuple);
+    calldata.extend_from_slice(&output_bytes);
+    calldata.extend_from_slice(&proof_bytes);
+    Ok(calldata)
+}
+
+/// `bytes` as a Solidity hex literal split over indented lines
+fn hex_lines(bytes: &[u8]) -> String {
+    hex::encode(bytes)
+        .as_bytes()
+        .chunks(MODULUS_LINE_DIGITS)
+        .map(|line| format!("        hex\"{}\"", String::from_utf8_lossy(line)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn abi_word(value: u64) -> [u8; WORD_BYTES] {
+    let mut word = [0u8; WORD_BYTES];
+    word[WORD_BYTES - 8..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Length word followed by the bytes, zero-padded to a whole word
+fn abi_bytes(bytes: &[u8]) -> Vec<u8> {
+    let padded = bytes.len().div_ceil(WORD_BYTES) * WORD_BYTES;
+    let mut encoded = abi_word(bytes.len() as u64).to_vec();
+    encoded.extend_from_slice(bytes);
+    encoded.resize(WORD_BYTES + padded, 0);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VDFInput;
+
+    fn output() -> VDFOutput {
+        VDFOutput {
+            input: VDFInput {
+                player_id: 4,
+                action_type: "craft".to_string(),
+                action_id: 17,
+                timestamp: 1234567890,
+                nonce: 3,
+                random_salt: 4096,
+            },
+            output: vec![1; ELEMENT_BYTES],
+            iterations: 600,
+            proof: vec![2; ELEMENT_BYTES],
+            computation_time: 0.5,
+            scheme: SchemeKind::Wesolowski,
+            parameters: ParameterSet::current(),
+        }
+    }
+
+    fn word_at(calldata: &[u8], offset: usize) -> u64 {
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&calldata[offset + WORD_BYTES - 8..offset + WORD_BYTES]);
+        u64::from_be_bytes(word)
+    }
+
+    #[test]
+    fn test_checked_in_verifier_is_current() {
+        let source = verifier_source(&BigUintRsaGroup::rsa_2048());
+        assert!(!source.contains("__"), "Unfilled placeholder in the template");
+        assert_eq!(
+            source,
+            include_str!("../../contracts/VDFVerifier.sol"),
+            "contracts/VDFVerifier.sol is stale, regenerate it with `cargo run --example solidity_verifier`"
+        );
+    }
+
+    #[test]
+    fn test_calldata_layout() {
+        let output = output();
+        let calldata = verify_calldata(&output).expect("Encoding failed");
+        assert_eq!(calldata[..4], verify_selector());
+
+        let args = &calldata[4..];
+        let tuple = word_at(args, 0) as usize;
+        assert_eq!(word_at(args, WORD_BYTES), 600);
+        assert_eq!(word_at(args, tuple), 4);
+        assert_eq!(word_at(args, tuple + 2 * WORD_BYTES), 17);
+        assert_eq!(word_at(args, tuple + 5 * WORD_BYTES), 4096);
+
+        let action_type = tuple + word_at(args, tuple + WORD_BYTES) as usize;
+        assert_eq!(word_at(args, action_type), 5);
+        assert_eq!(&args[action_type + WORD_BYTES..action_type + WORD_BYTES + 5], b"craft");
+
+        let proof = word_at(args, 3 * WORD_BYTES) as usize;
+        assert_eq!(word_at(args, proof) as usize, ELEMENT_BYTES);
+        assert_eq!(&args[proof + WORD_BYTES..], output.proof.as_slice());
+        assert_eq!(args.len() % WORD_BYTES, 0);
+    }
+
+    #[test]
+    fn test_calldata_rejects_pietrzak() {
+        let mut pietrzak = output();
+        pietrzak.scheme = SchemeKind::Pietrzak;
+        assert!(verify_calldata(&pietrzak).is_err());
+
+        let mut wrong_length = output();
+        wrong_length.proof.push(0);
+        assert!(verify_calldata(&wrong_length).is_err());
+
+        let mut legacy = output();
+        legacy.parameters = ParameterSet::legacy();
+        assert!(matches!(verify_calldata(&legacy), Err(VdfError::ParameterMismatch { .. })));
+    }
+}
+
+/// Runs the generated contract in revm against proofs from `VDFEngine`
+///
+/// Compiling needs `solc` on the `PATH` or in `SOLC`, so the test is ignored
+/// by default; run it with `cargo test -- --ignored`.
+#[cfg(all(test, feature = "gmp"))]
+mod evm_tests {
+    use std::process::Command;
+
+    use revm::db::{CacheDB, EmptyDB};
+    use revm::primitives::{Address, Bytes, ExecutionResult, Output, TxKind};
+    use revm::Evm;
+
+    use super::*;
+    use crate::{VDFEngine, VDFInput};
+
+    const GAS_LIMIT: u64 = 30_000_000;
+
+    fn compile() -> Vec<u8> {
+        let solc = std::env::var("SOLC").unwrap_or_else(|_| "solc".to_string());
+        let path = std::env::temp_dir().join(format!("{}-{}.sol", VERIFIER_CONTRACT, std::process::id()));
+        std::fs::write(&path, verifier_source(&BigUintRsaGroup::rsa_2048())).expect("Failed to write contract");
+        let result = Command::new(solc).arg("--optimize").arg("--combined-json").arg("bin").arg(&path).output();
+        let _ = std::fs::remove_file(&path);
+
+        let result = result.expect("Failed to run solc, set SOLC to its path");
+        assert!(result.status.success(), "solc failed: {}", String::from_utf8_lossy(&result.stderr));
+        let compiled: serde_json::Value = serde_json::from_slice(&result.stdout).expect("Invalid solc output");
+        let contracts = compiled["contracts"].as_object().expect("Missing contracts in solc output");
+        let (_, contract) = contracts
+            .iter()
+            .find(|(name, _)| name.ends_with(&format!(":{}", VERIFIER_CONTRACT)))
+            .expect("Verifier missing from solc output");
+        hex::decode(contract["bin"].as_str().expect("Missing bytecode")).expect("Invalid bytecode hex")
+    }
+
+    struct Chain {
+        evm: Evm<'static, (), CacheDB<EmptyDB>>,
+        verifier: Address,
+    }
+
+    impl Chain {
+        fn deploy(bytecode: Vec<u8>) -> Self {
+            let mut evm = Evm::builder()
+                .with_db(CacheDB::new(EmptyDB::default()))
+                .modify_tx_env(|tx| {
+                    tx.transact_to = TxKind::Create;
+                    tx.data = Bytes::from(bytecode);
+                    tx.gas_limit = GAS_LIMIT;
+                })
+                .build();
+            let verifier = match evm.transact_commit().expect("Deployment failed") {
+                ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => address,
+                result => panic!("Deployment failed: {:?}", result),
+            };
+            Self { evm, verifier }
+        }
+
+        fn verify(&mut self, output: &VDFOutput) -> bool {
+            let tx = self.evm.tx_mut();
+            tx.transact_to = TxKind::Call(self.verifier);
+            tx.data = Bytes::from(verify_calldata(output).expect("Encoding failed"));
+            tx.gas_limit = GAS_LIMIT;
+            match self.evm.transact().expect("Call failed").result {
+                ExecutionResult::Success { output: Output::Call(data), .. } => {
+                    assert_eq!(data.len(), WORD_BYTES);
+                    data[WORD_BYTES - 1] == 1
+                }
+                result => panic!("Verifier call failed: {:?}", result),
+            }
+        }
+    }
+
+    fn input(action_id: u64) -> VDFInput {
+        VDFInput {
+            player_id: 4,
+            action_type: "craft".to_string(),
+            action_id,
+            timestamp: 1234567890,
+            nonce: 3,
+            random_salt: 4096,
+        }
+    }
+
+    #[test]
+    #[ignore = "needs solc on the PATH or in SOLC"]
+    fn test_engine_outputs_in_evm() {
+        let mut chain = Chain::deploy(compile());
+        let engine = VDFEngine::new();
+
+        for (action_id, iterations) in [(17, 600), (18, 1), (19, 2049)] {
+            let output = engine.compute(&input(action_id), iterations).expect("VDF computation failed");
+            assert!(engine.verify_output(&output).expect("Verification failed"));
+            assert!(chain.verify(&output), "Valid output rejected for {} iterations", iterations);
+        }
+
+        let output = engine.compute(&input(17), 600).expect("VDF computation failed");
+
+        let mut replayed = output.clone();
+        replayed.iterations = 601;
+        assert!(!chain.verify(&replayed));
+
+        let mut other_action = output.clone();
+        other_action.input.action_type = "gather".to_string();
+        assert!(!chain.verify(&other_action));
+
+        let mut other_player = output.clone();
+        other_player.input.player_id = 5;
+        assert!(!chain.verify(&other_player));
+
+        let mut swapped = output.clone();
+        std::mem::swap(&mut swapped.output, &mut swapped.proof);
+        assert!(!chain.verify(&swapped));
+
+        let mut zero = output.clone();
+        zero.output = vec![0; ELEMENT_BYTES];
+        zero.proof = vec![0; ELEMENT_BYTES];
+        assert!(!chain.verify(&zero));
+
+        // The other sign of a valid output and proof is not canonical
+        let group = BigUintRsaGroup::rsa_2048();
+        let negate = |bytes: &[u8]| {
+            group.encode(&(group.modulus() - num_bigint::BigUint::from_bytes_be(bytes))).expect("Encoding failed")
+        };
+        let mut negated = output.clone();
+        negated.output = negate(&output.output);
+        negated.proof = negate(&output.proof);
+        assert!(!chain.verify(&negated));
+
+        let mut one = output.clone();
+        one.proof = group.encode(&num_bigint::BigUint::from(1u32)).expect("Encoding failed");
+        assert!(!chain.verify(&one));
+
+        let mut unreduced = output;
+        unreduced.output = group.description();
+        assert!(!chain.verify(&unreduced));
+    }
+}
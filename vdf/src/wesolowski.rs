@@ -0,0 +1,300 @@
+//! Wesolowski proofs of exponentiation
+//!
+//! The proof is a single group element `pi = x^floor(2^T / l)` for a prime
+//! challenge `l` derived from the statement with the versioned
+//! `hash_to_prime`. Verification costs two exponentiations with exponents
+//! smaller than `l`.
+//!
+//! The prover runs on GMP; verification is generic over `VdfGroup`.
+
+use num_bigint::BigUint;
+#[cfg(feature = "gmp")]
+use rand::Rng;
+#[cfg(feature = "gmp")]
+use rug::Integer;
+
+#[cfg(feature = "gmp")]
+use crate::checkpoint::{CheckpointPlan, SquaringTrace};
+use crate::error::VdfError;
+use crate::group::VdfGroup;
+use crate::hash_to_prime::hash_to_prime;
+#[cfg(feature = "gmp")]
+use crate::rsa_group::{canonical, to_integer, RugRsaGroup};
+#[cfg(feature = "gmp")]
+use crate::scheme::{SchemeKind, VdfScheme};
+
+/// Wesolowski single-element proof
+#[cfg(feature = "gmp")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Wesolowski;
+
+#[cfg(feature = "gmp")]
+impl VdfScheme for Wesolowski {
+    fn kind(&self) -> SchemeKind {
+        SchemeKind::Wesolowski
+    }
+
+    fn prove(&self, modulus: &Integer, context: &[u8], trace: &SquaringTrace, input: &Integer, output: &Integer) -> Result<Vec<Integer>, VdfError> {
+        self.prove_parallel(modulus, context, trace, input, output, 1)
+    }
+
+    /// Computes `pi = input^floor(2^iterations / l)` from the checkpoints in
+    /// `trace`, see `accumulate`. The checkpoints are split into `threads`
+    /// contiguous shards; every shard contributes `prod c_i^(q_i)` for its
+    /// own checkpoints `c_i` and quotient digits `q_i`, and the partial
+    /// products multiply to `pi`.
+    ///
+    /// The challenge `l` depends on `output`, so no digit is known before
+    /// the last squaring and no shard can start while the chain is still
+    /// being squared; this runs strictly after it. The shards shorten the
+    /// remaining work instead: each worker does `interval` squarings plus its
+    /// share of the `T / window_bits` multiplications, with its own bucket
+    /// table.
+    fn prove_parallel(&self, modulus: &Integer, context: &[u8], trace: &SquaringTrace, input: &Integer, output: &Integer, threads: usize) -> Result<Vec<Integer>, VdfError> {
+        let plan = trace.plan();
+        let output = canonical(modulus, output.clone());
+        let challenge = challenge(modulus, plan.iterations, context, input, &output)?;
+        let checkpoints = trace.checkpoints();
+
+        let shard_len = checkpoints.len().div_ceil(threads.max(1)).max(1);
+        if shard_len >= checkpoints.len() {
+            return Ok(vec![canonical(modulus, accumulate(modulus, &challenge, plan, checkpoints, 0)?)]);
+        }
+
+        let partials = std::thread::scope(|scope| {
+            let workers: Vec<_> = checkpoints.chunks(shard_len)
+                .enumerate()
+                .map(|(shard, chunk)| {
+                    let challenge = &challenge;
+                    scope.spawn(move || accumulate(modulus, challenge, plan, chunk, shard * shard_len))
+                })
+                .collect();
+            workers.into_iter()
+                .map(|worker| worker.join().expect("Prover thread panicked"))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let proof = partials.into_iter().fold(Integer::from(1), |proof, partial| (proof * partial) % modulus);
+        Ok(vec![canonical(modulus, proof)])
+    }
+
+    fn verify(&self, modulus: &Integer, context: &[u8], input: &Integer, output: &Integer, iterations: u64, proof: &[Integer]) -> Result<bool, VdfError> {
+        match proof {
+            [proof] => verify_in_group(&RugRsaGroup::new(modulus.clone()), context, input, output, iterations, proof),
+            _ => Ok(false),
+        }
+    }
+}
+
+/// `prod c_i^(q_i)` over `checkpoints`, the slice of the trace starting at
+/// checkpoint `first_index`
+///
+/// The quotient `floor(2^T / l)` is produced digit by digit by long division
+/// of `2^T` by `l`; checkpoint `i` carries the digits at positions
+/// `i * stride + t`, and the partial products for each offset `t` are
+/// combined with `window_bits` squarings between them.
+#[cfg(feature = "gmp")]
+pub(crate) fn accumulate(modulus: &Integer, challenge: &Integer, plan: &CheckpointPlan, checkpoints: &[Integer], first_index: usize) -> Result<Integer, VdfError> {
+    let window = plan.window_bits as u64;
+    let digits = plan.digit_count();
+    let two = Integer::from(2);
+
+    // 2^interval mod l moves the long-division remainder from one
+    // checkpoint's digit to the same digit of the previous checkpoint
+    let step = two.clone().pow_mod(&Integer::from(plan.interval), challenge)
+        .map_err(|e| VdfError::Arithmetic(format!("Modular exponentiation error: {}", e)))?;
+
+    let mut buckets = vec![Integer::from(1); 1 << plan.window_bits];
+    let mut proof = Integer::from(1);
+    for offset in (0..plan.stride).rev() {
+        for _ in 0..window {
+            proof = proof.square() % modulus;
+        }
+
+        // Multiply every checkpoint into the bucket of its digit value
+        let mut remainder = Integer::new();
+        let mut previous_shift: Option<u64> = None;
+        for (index, checkpoint) in checkpoints.iter().enumerate().rev() {
+            let position = (first_index + index) as u64 * plan.stride + offset;
+            if position >= digits {
+                continue;
+            }
+
+            // Digit covers bits [shift, shift + width) of the quotient
+            let remaining = plan.iterations - position * window;
+            let width = remaining.min(window);
+            let shift = remaining - width;
+            remainder = match previous_shift {
+                Some(previous) if shift == previous + plan.interval => (remainder * &step) % challenge,
+                _ => two.clone().pow_mod(&Integer::from(shift), challenge)
+                    .map_err(|e| VdfError::Arithmetic(format!("Modular exponentiation error: {}", e)))?,
+            };
+            previous_shift = Some(shift);
+
+            let digit = (remainder.clone() << width as u32) / challenge;
+            let digit = digit.to_usize()
+                .ok_or_else(|| VdfError::Arithmetic("Quotient digit out of range".to_string()))?;
+            if digit != 0 {
+                buckets[digit] *= checkpoint;
+                buckets[digit] %= modulus;
+            }
+        }
+
+        // prod_b bucket_b^b as a running product of suffix products
+        let mut suffix = Integer::from(1);
+        let mut partial = Integer::from(1);
+        for bucket in buckets.iter_mut().skip(1).rev() {
+            if *bucket != 1 {
+                suffix = (suffix * &*bucket) % modulus;
+                *bucket = Integer::from(1);
+            }
+            if suffix != 1 {
+                partial = (partial * &suffix) % modulus;
+            }
+        }
+        proof = (proof * partial) % modulus;
+    }
+
+    Ok(proof)
+}
+
+/// Check `input^r × pi^l = output` in `group`, with `l` the challenge prime
+/// and `r = 2^iterations mod l`
+///
+/// `output` must be a canonical group element and `proof` one as well or the
+/// identity, see `is_valid_proof`; `input` is derived by the caller and
+/// trusted.
+pub fn verify_in_group<G: VdfGroup>(group: &G, context: &[u8], input: &G::Element, output: &G::Element, iterations: u64, proof: &G::Element) -> Result<bool, VdfError> {
+    if !group.is_valid(output) || !is_valid_proof(group, proof) {
+        return Ok(false);
+    }
+
+    // Recompute challenge
+    let challenge = challenge_in(group, iterations, context, input, output)?;
+
+    // Calculate remainder: r = 2^iterations mod l
+    let remainder = BigUint::from(2u32).modpow(&BigUint::from(iterations), &challenge);
+
+    // Verify equation: input^r × pi^l = output
+    let left_side = group.mul(&group.exp(input, &remainder), &group.exp(proof, &challenge));
+    Ok(left_side == *output)
+}
+
+/// Whether `proof` may stand as a Wesolowski proof in `group`
+///
+/// Unlike outputs, proofs may be the identity: with `2^iterations < l`, so
+/// for every `iterations < 256`, the quotient `floor(2^iterations / l)` is
+/// zero and the honest proof is `input^0`. The check then reduces to
+/// `input^r = output` with `r = 2^iterations`, which only the true output
+/// passes.
+pub(crate) fn is_valid_proof<G: VdfGroup>(group: &G, proof: &G::Element) -> bool {
+    group.is_valid(proof) || *proof == group.identity()
+}
+
+/// Challenge prime for `output = input^(2^iterations)` in `group`, see `hash_to_prime`
+pub fn challenge_in<G: VdfGroup>(group: &G, iterations: u64, context: &[u8], input: &G::Element, output: &G::Element) -> Result<BigUint, VdfError> {
+    hash_to_prime(
+        &group.description(),
+        iterations,
+        context,
+        &group.encode(input)?,
+        &group.encode(output)?,
+    )
+}
+
+/// Wesolowski statement with its challenge, prepared for batch verification
+#[cfg(feature = "gmp")]
+pub(crate) struct BatchEntry {
+    input: Integer,
+    output: Integer,
+    proof: Integer,
+    challenge: Integer,
+    remainder: Integer,
+}
+
+#[cfg(feature = "gmp")]
+impl BatchEntry {
+    pub(crate) fn new(modulus: &Integer, context: &[u8], input: Integer, output: Integer, iterations: u64, proof: Integer) -> Result<Self, VdfError> {
+        let challenge = challenge(modulus, iterations, context, &input, &output)?;
+        let remainder = Integer::from(2).pow_mod(&Integer::from(iterations), &challenge)
+            .map_err(|e| VdfError::Arithmetic(format!("Modular exponentiation error: {}", e)))?;
+        Ok(Self { input, output, proof, challenge, remainder })
+    }
+
+    /// Exact single-statement check `input^r × pi^l ≡ ±output`
+    pub(crate) fn verify(&self, modulus: &Integer) -> bool {
+        let left_side = multi_exp(modulus, &[
+            (&self.input, self.remainder.clone()),
+            (&self.proof, self.challenge.clone()),
+        ]);
+        canonical(modulus, left_side) == self.output
+    }
+}
+
+/// Small-exponent batch test: with random 128-bit `rho_i`, checks
+/// `prod (input_i^r_i × pi_i^l_i)^rho_i ≡ ±prod output_i^rho_i`.
+///
+/// Both sides are compared in the quotient group `{±1}`, where `-1`, the
+/// only known element of small order mod an RSA modulus, is the identity. A
+/// false statement passes with probability at most `2^-128`.
+#[cfg(feature = "gmp")]
+pub(crate) fn verify_batch(modulus: &Integer, entries: &[&BatchEntry]) -> bool {
+    let mut rng = rand::thread_rng();
+    let weights: Vec<Integer> = entries.iter()
+        .map(|_| Integer::from(rng.gen::<u128>()))
+        .collect();
+
+    let mut left_terms = Vec::with_capacity(2 * entries.len());
+    let mut right_terms = Vec::with_capacity(entries.len());
+    for (entry, weight) in entries.iter().zip(&weights) {
+        left_terms.push((&entry.input, Integer::from(&entry.remainder * weight)));
+        left_terms.push((&entry.proof, Integer::from(&entry.challenge * weight)));
+        right_terms.push((&entry.output, weight.clone()));
+    }
+
+    canonical(modulus, multi_exp(modulus, &left_terms)) == canonical(modulus, multi_exp(modulus, &right_terms))
+}
+
+/// Bits per window in `multi_exp`
+#[cfg(feature = "gmp")]
+const MULTI_EXP_WINDOW: u32 = 4;
+
+/// `prod base^exponent mod modulus` with one shared chain of squarings
+/// (Straus' method with fixed 4-bit windows)
+#[cfg(feature = "gmp")]
+fn multi_exp(modulus: &Integer, terms: &[(&Integer, Integer)]) -> Integer {
+    let tables: Vec<Vec<Integer>> = terms.iter()
+        .map(|(base, _)| {
+            let mut table = vec![Integer::from(1), Integer::from(*base % modulus)];
+            for _ in 2..1u32 << MULTI_EXP_WINDOW {
+                let next = Integer::from(&table[table.len() - 1] * &table[1]) % modulus;
+                table.push(next);
+            }
+            table
+        })
+        .collect();
+
+    let bits = terms.iter().map(|(_, exponent)| exponent.significant_bits()).max().unwrap_or(0);
+    let mut result = Integer::from(1);
+    for window in (0..bits.div_ceil(MULTI_EXP_WINDOW)).rev() {
+        for _ in 0..MULTI_EXP_WINDOW {
+            result = result.square() % modulus;
+        }
+        for ((_, exponent), table) in terms.iter().zip(&tables) {
+            let digit = (0..MULTI_EXP_WINDOW)
+                .filter(|bit| exponent.get_bit(window * MULTI_EXP_WINDOW + bit))
+                .fold(0usize, |digit, bit| digit | 1 << bit);
+            if digit != 0 {
+                result = (result * &table[digit]) % modulus;
+            }
+        }
+    }
+    result
+}
+
+/// `challenge_in` for the RSA group of `modulus`, as a GMP integer
+#[cfg(feature = "gmp")]
+pub(crate) fn challenge(modulus: &Integer, iterations: u64, context: &[u8], input: &Integer, output: &Integer) -> Result<Integer, VdfError> {
+    let group = RugRsaGroup::new(modulus.clone());
+    Ok(to_integer(&challenge_in(&group, iterations, context, input, output)?))
+}
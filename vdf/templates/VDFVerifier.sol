@@ -0,0 +1,358 @@
+// SPDX-License-Identifier: MIT
+// Generated by zkgame-vdf (`cargo run --example solidity_verifier`), do not edit.
+pragma solidity ^0.8.20;
+
+/**
+ * @title VDFVerifier
+ * @dev Verifies Wesolowski VDF outputs produced by zkgame-vdf
+ * @notice Checks input^r * proof^l == output in (Z/MODULUS)* / {1, -1}, where l
+ * is the hash-to-prime v__HASH_TO_PRIME_VERSION__ challenge and r = 2^iterations mod l.
+ * Output and proof must be canonical representatives 1 < x <= (MODULUS - 1) / 2,
+ * except that the proof may be 1, the honest proof when 2^iterations < l.
+ * The input element is derived from the action details, so an output only
+ * verifies for the action it was computed for.
+ */
+contract VDFVerifier {
+    // Action details the VDF input is derived from, as in zkgame_vdf::VDFInput
+    struct VDFInput {
+        uint64 playerId;
+        string actionType;
+        uint64 actionId;
+        uint64 timestamp;
+        uint64 nonce;
+        uint64 randomSalt;
+    }
+
+    // Size of one big-endian group element
+    uint256 private constant ELEMENT_BYTES = __ELEMENT_BYTES__;
+    uint256 private constant LIMB_MASK = type(uint128).max;
+
+    // Statement version and scheme tag bound into the challenge context
+    uint8 private constant PROOF_VERSION = __PROOF_VERSION__;
+    uint8 private constant WESOLOWSKI_TAG = __WESOLOWSKI_TAG__;
+
+    // Hash-to-prime domain separation tag and candidate limit
+    bytes private constant HASH_TO_PRIME_TAG = "__HASH_TO_PRIME_TAG__";
+    uint256 private constant MAX_ATTEMPTS = __MAX_ATTEMPTS__;
+
+    bytes private constant MODULUS =
+__MODULUS__;
+
+    // (MODULUS - 1) / 2, the largest canonical representative
+    bytes private constant HALF_MODULUS =
+__HALF_MODULUS__;
+
+    /**
+     * @dev Verify a Wesolowski VDF output
+     * @param input Action details the output was computed for
+     * @param iterations Number of sequential squarings
+     * @param output Big-endian output element
+     * @param proof Big-endian proof element
+     * @return True if the proof is valid for this action and iteration count
+     */
+    function verify(
+        VDFInput calldata input,
+        uint64 iterations,
+        bytes calldata output,
+        bytes calldata proof
+    ) external view returns (bool) {
+        if (output.length != ELEMENT_BYTES || proof.length != ELEMENT_BYTES) {
+            return false;
+        }
+        bytes memory y = output;
+        bytes memory pi = proof;
+        // Zero and ±1 would verify statements without the squarings, and a
+        // second sign would give every output two encodings. The proof
+        // x^floor(2^iterations / l) is 1 when 2^iterations < l, which leaves
+        // x^r == y to check
+        if (!isElement(y) || !(isElement(pi) || isIdentity(pi))) {
+            return false;
+        }
+
+        bytes memory x = inputElement(input);
+        uint256 l = hashToPrime(iterations, challengeContext(input.actionType), x, y);
+        if (l == 0) {
+            return false;
+        }
+        uint256 r = modExp256(2, iterations, l);
+
+        bytes memory left = mulMod(
+            modExp(x, abi.encodePacked(r), MODULUS),
+            modExp(pi, abi.encodePacked(l), MODULUS)
+        );
+        // Equal up to sign, left may be either representative
+        bytes32 expected = keccak256(y);
+        return keccak256(left) == expected || keccak256(negate(left)) == expected;
+    }
+
+    /**
+     * @dev SHA-256 of the action details with little-endian integers, read
+     * as a little-endian number and widened to a group element
+     */
+    function inputElement(VDFInput calldata input) private pure returns (bytes memory) {
+        bytes32 digest = sha256(abi.encodePacked(
+            littleEndian(input.playerId),
+            input.actionType,
+            littleEndian(input.actionId),
+            littleEndian(input.timestamp),
+            littleEndian(input.nonce),
+            littleEndian(input.randomSalt)
+        ));
+
+        uint256 seed = 0;
+        uint256 word = uint256(digest);
+        for (uint256 i = 0; i < 32; i++) {
+            seed = (seed << 8) | (word & 0xff);
+            word >>= 8;
+        }
+        return abi.encodePacked(new bytes(ELEMENT_BYTES - 32), seed);
+    }
+
+    function littleEndian(uint64 value) private pure returns (bytes8) {
+        uint64 reversed = 0;
+        for (uint256 i = 0; i < 8; i++) {
+            reversed = (reversed << 8) | (value & 0xff);
+            value >>= 8;
+        }
+        return bytes8(reversed);
+    }
+
+    /**
+     * @dev PROOF_VERSION | WESOLOWSKI_TAG | uint32 length | actionType
+     */
+    function challengeContext(string calldata actionType) private pure returns (bytes memory) {
+        return abi.encodePacked(PROOF_VERSION, WESOLOWSKI_TAG, uint32(bytes(actionType).length), actionType);
+    }
+
+    /**
+     * @dev Challenge prime for the statement, or 0 if no candidate was prime
+     * @notice h_i = SHA-256(TAG | u32 len | modulus | u64 iterations | u32 len | context
+     * | u32 len | input | u32 len | output | u32 i), l = h_i | 2^255 | 1 for the
+     * first i passing Miller-Rabin with the fixed bases
+     */
+    function hashToPrime(
+        uint64 iterations,
+        bytes memory context,
+        bytes memory input,
+        bytes memory output
+    ) private view returns (uint256) {
+        bytes memory statement = abi.encodePacked(
+            uint32(ELEMENT_BYTES),
+            MODULUS,
+            iterations,
+            uint32(context.length),
+            context
+        );
+        bytes memory message = abi.encodePacked(
+            HASH_TO_PRIME_TAG,
+            statement,
+            uint32(ELEMENT_BYTES),
+            input,
+            uint32(ELEMENT_BYTES),
+            output,
+            uint32(0)
+        );
+
+        for (uint256 counter = 0; counter < MAX_ATTEMPTS; counter++) {
+            // Overwrite the trailing u32 counter in place
+            assembly {
+                let last := add(message, mload(message))
+                mstore(last, or(and(mload(last), not(0xffffffff)), counter))
+            }
+            uint256 candidate = uint256(sha256(message)) | (1 << 255) | 1;
+            if (isProbablePrime(candidate)) {
+                return candidate;
+            }
+        }
+        return 0;
+    }
+
+    /**
+     * @dev Miller-Rabin with the fixed bases, for odd n above the largest base
+     */
+    function isProbablePrime(uint256 n) private view returns (bool) {
+        uint256 d = n - 1;
+        uint256 s = 0;
+        while (d & 1 == 0) {
+            d >>= 1;
+            s++;
+        }
+
+        uint256[__BASE_COUNT__] memory bases = [__MILLER_RABIN_BASES__];
+        for (uint256 i = 0; i < bases.length; i++) {
+            if (!millerRabinRound(n, d, s, bases[i])) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    function millerRabinRound(uint256 n, uint256 d, uint256 s, uint256 base) private view returns (bool) {
+        uint256 x = modExp256(base, d, n);
+        if (x == 1 || x == n - 1) {
+            return true;
+        }
+        for (uint256 i = 1; i < s; i++) {
+            x = mulmod(x, x, n);
+            if (x == n - 1) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /**
+     * @dev 1 < element <= HALF_MODULUS for a big-endian element of ELEMENT_BYTES
+     */
+    function isElement(bytes memory element) private pure returns (bool) {
+        bytes memory half = HALF_MODULUS;
+        bool aboveOne = false;
+        // Sign of element - HALF_MODULUS, decided by the first differing word
+        int256 order = 0;
+        for (uint256 offset = 32; offset <= ELEMENT_BYTES; offset += 32) {
+            uint256 word;
+            uint256 bound;
+            assembly {
+                word := mload(add(element, offset))
+                bound := mload(add(half, offset))
+            }
+            if (word > (offset == ELEMENT_BYTES ? 1 : 0)) {
+                aboveOne = true;
+            }
+            if (order == 0 && word != bound) {
+                order = word < bound ? int256(-1) : int256(1);
+            }
+        }
+        return aboveOne && order <= 0;
+    }
+
+    /**
+     * @dev Whether element is the big-endian encoding of 1
+     */
+    function isIdentity(bytes memory element) private pure returns (bool) {
+        for (uint256 offset = 32; offset <= ELEMENT_BYTES; offset += 32) {
+            uint256 word;
+            assembly {
+                word := mload(add(element, offset))
+            }
+            if (word != (offset == ELEMENT_BYTES ? 1 : 0)) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /**
+     * @dev MODULUS - value for a reduced value of ELEMENT_BYTES
+     */
+    function negate(bytes memory value) private pure returns (bytes memory result) {
+        bytes memory modulus = MODULUS;
+        result = new bytes(ELEMENT_BYTES);
+        uint256 borrow = 0;
+        for (uint256 offset = ELEMENT_BYTES; offset > 0; offset -= 32) {
+            uint256 word;
+            uint256 bound;
+            assembly {
+                word := mload(add(value, offset))
+                bound := mload(add(modulus, offset))
+            }
+            uint256 difference;
+            unchecked {
+                difference = bound - word - borrow;
+            }
+            borrow = word > bound || (word == bound && borrow == 1) ? 1 : 0;
+            assembly {
+                mstore(add(result, offset), difference)
+            }
+        }
+    }
+
+    /**
+     * @dev a * b mod MODULUS: schoolbook product in 128-bit limbs, reduced
+     * with the modexp precompile and exponent 1
+     */
+    function mulMod(bytes memory a, bytes memory b) private view returns (bytes memory) {
+        uint256[] memory x = toLimbs(a);
+        uint256[] memory y = toLimbs(b);
+
+        // Column sums stay below 2^133, so they cannot overflow
+        uint256[] memory columns = new uint256[](x.length + y.length);
+        for (uint256 i = 0; i < x.length; i++) {
+            for (uint256 j = 0; j < y.length; j++) {
+                uint256 term = x[i] * y[j];
+                columns[i + j] += term & LIMB_MASK;
+                columns[i + j + 1] += term >> 128;
+            }
+        }
+
+        uint256 words = columns.length / 2;
+        bytes memory product = new bytes(32 * words);
+        uint256 carry = 0;
+        for (uint256 w = 0; w < words; w++) {
+            uint256 low = columns[2 * w] + carry;
+            uint256 high = columns[2 * w + 1] + (low >> 128);
+            carry = high >> 128;
+            uint256 word = (high << 128) | (low & LIMB_MASK);
+            assembly {
+                mstore(add(product, mul(sub(words, w), 0x20)), word)
+            }
+        }
+        return modExp(product, hex"01", MODULUS);
+    }
+
+    /**
+     * @dev Little-endian 128-bit limbs of a big-endian number of whole words
+     */
+    function toLimbs(bytes memory value) private pure returns (uint256[] memory limbs) {
+        uint256 words = value.length / 32;
+        limbs = new uint256[](2 * words);
+        for (uint256 w = 0; w < words; w++) {
+            uint256 word;
+            assembly {
+                word := mload(add(value, mul(sub(words, w), 0x20)))
+            }
+            limbs[2 * w] = word & LIMB_MASK;
+            limbs[2 * w + 1] = word >> 128;
+        }
+    }
+
+    /**
+     * @dev base^exponent mod modulus through the EIP-198 precompile
+     */
+    function modExp(
+        bytes memory base,
+        bytes memory exponent,
+        bytes memory modulus
+    ) private view returns (bytes memory result) {
+        bytes memory input = abi.encodePacked(
+            base.length,
+            exponent.length,
+            modulus.length,
+            base,
+            exponent,
+            modulus
+        );
+        result = new bytes(modulus.length);
+        bool success;
+        assembly {
+            success := staticcall(gas(), 0x05, add(input, 0x20), mload(input), add(result, 0x20), mload(modulus))
+        }
+        require(success, "VDFVerifier: modexp failed");
+    }
+
+    function modExp256(uint256 base, uint256 exponent, uint256 modulus) private view returns (uint256 result) {
+        bool success;
+        assembly {
+            let p := mload(0x40)
+            mstore(p, 0x20)
+            mstore(add(p, 0x20), 0x20)
+            mstore(add(p, 0x40), 0x20)
+            mstore(add(p, 0x60), base)
+            mstore(add(p, 0x80), exponent)
+            mstore(add(p, 0xa0), modulus)
+            success := staticcall(gas(), 0x05, p, 0xc0, p, 0x20)
+            result := mload(p)
+        }
+        require(success, "VDFVerifier: modexp failed");
+    }
+}
@@ -0,0 +1,248 @@
+//! Property tests: malformed and adversarial proofs are never accepted
+//!
+//! Every case starts from a valid output, alters it or replaces parts of it
+//! with generated values, and checks that neither `VDFEngine` nor the pure
+//! Rust verifier accepts the result. Run with `cargo test --test adversarial`.
+
+#![cfg(feature = "gmp")]
+
+use num_bigint::BigUint;
+use proptest::prelude::*;
+use proptest::sample::select;
+use rug::Integer;
+use zkgame_vdf::{
+    element_from_bytes, verify_output_in, BigUintRsaGroup, SchemeKind, VDFEngine, VDFInput, VDFOutput, VdfGroup,
+    ELEMENT_BYTES,
+};
+
+const ITERATIONS: u64 = 300;
+
+/// Cases per property; every case runs one or two full verifications
+const CASES: u32 = 64;
+
+struct Fixtures {
+    engine: VDFEngine,
+    group: BigUintRsaGroup,
+    wesolowski: VDFOutput,
+    pietrzak: VDFOutput,
+}
+
+impl Fixtures {
+    fn new() -> Self {
+        let engine = VDFEngine::new();
+        let wesolowski = engine.compute(&input(1), ITERATIONS).expect("VDF computation failed");
+        let pietrzak = VDFEngine::new().with_scheme(SchemeKind::Pietrzak)
+            .compute(&input(1), ITERATIONS).expect("VDF computation failed");
+        Self {
+            engine,
+            group: BigUintRsaGroup::rsa_2048(),
+            wesolowski,
+            pietrzak,
+        }
+    }
+
+    fn output(&self, scheme: SchemeKind) -> &VDFOutput {
+        match scheme {
+            SchemeKind::Wesolowski => &self.wesolowski,
+            SchemeKind::Pietrzak => &self.pietrzak,
+        }
+    }
+
+    /// Whether either verifier accepts `output`; errors count as rejections
+    fn accepts(&self, output: &VDFOutput) -> bool {
+        matches!(self.engine.verify_output(output), Ok(true))
+            || matches!(verify_output_in(&self.group, output), Ok(true))
+    }
+
+    fn modulus(&self) -> &Integer {
+        self.engine.group().modulus()
+    }
+}
+
+fn input(action_id: u64) -> VDFInput {
+    VDFInput {
+        player_id: 11,
+        action_type: "craft".to_string(),
+        action_id,
+        timestamp: 1234567890,
+        nonce: 5,
+        random_salt: 8080,
+    }
+}
+
+fn scheme() -> impl Strategy<Value = SchemeKind> {
+    select(vec![SchemeKind::Wesolowski, SchemeKind::Pietrzak])
+}
+
+/// `N - value` for an encoded element
+fn negate(group: &BigUintRsaGroup, value: &[u8]) -> Vec<u8> {
+    group.encode(&(group.modulus() - BigUint::from_bytes_be(value))).expect("Element fits")
+}
+
+#[test]
+fn test_fixtures_verify() {
+    let fixtures = Fixtures::new();
+    for scheme in [SchemeKind::Wesolowski, SchemeKind::Pietrzak] {
+        assert!(fixtures.accepts(fixtures.output(scheme)));
+    }
+}
+
+#[test]
+fn test_random_elements_are_rejected() {
+    let fixtures = Fixtures::new();
+    proptest!(ProptestConfig::with_cases(CASES), |(
+        scheme in scheme(),
+        output in prop::collection::vec(any::<u8>(), ELEMENT_BYTES),
+        proof in prop::collection::vec(any::<u8>(), ELEMENT_BYTES..=3 * ELEMENT_BYTES),
+    )| {
+        let mut forged = fixtures.output(scheme).clone();
+        forged.output = output;
+        forged.proof = proof;
+        prop_assert!(!fixtures.accepts(&forged));
+    });
+}
+
+#[test]
+fn test_bit_flips_are_rejected() {
+    let fixtures = Fixtures::new();
+    proptest!(ProptestConfig::with_cases(CASES), |(scheme in scheme(), in_proof in any::<bool>(), bit in any::<prop::sample::Index>())| {
+        let mut altered = fixtures.output(scheme).clone();
+        let target = if in_proof { &mut altered.proof } else { &mut altered.output };
+        let bit = bit.index(target.len() * 8);
+        target[bit / 8] ^= 1 << (bit % 8);
+        prop_assert!(!fixtures.accepts(&altered));
+    });
+}
+
+#[test]
+fn test_other_representatives_are_rejected() {
+    let fixtures = Fixtures::new();
+    let output = fixtures.wesolowski.output_element().expect("Decoding failed");
+    let proof = element_from_bytes(&fixtures.wesolowski.proof).expect("Decoding failed");
+    proptest!(ProptestConfig::with_cases(CASES), |(
+        negate_output in any::<bool>(),
+        negate_proof in any::<bool>(),
+        output_shift in -3i32..=3,
+        proof_shift in -3i32..=3,
+    )| {
+        prop_assume!(negate_output || negate_proof || output_shift != 0 || proof_shift != 0);
+        let shifted = |value: &Integer, negate: bool, shift: i32| {
+            let value = if negate { Integer::from(-value) } else { value.clone() };
+            value + Integer::from(fixtures.modulus() * shift)
+        };
+        let forged_output = shifted(&output, negate_output, output_shift);
+        let forged_proof = shifted(&proof, negate_proof, proof_shift);
+        let input = &fixtures.wesolowski.input;
+        prop_assert!(!fixtures.engine.verify(input, &forged_output, ITERATIONS, &forged_proof).unwrap_or(false));
+    });
+
+    // The same classes through the byte encoding
+    let mut negated = fixtures.wesolowski.clone();
+    negated.output = negate(&fixtures.group, &negated.output);
+    negated.proof = negate(&fixtures.group, &negated.proof);
+    assert!(!fixtures.accepts(&negated));
+
+    let mut negated = fixtures.pietrzak.clone();
+    negated.output = negate(&fixtures.group, &negated.output);
+    assert!(!fixtures.accepts(&negated));
+}
+
+#[test]
+fn test_low_order_elements_are_rejected() {
+    let fixtures = Fixtures::new();
+    let modulus = fixtures.modulus().clone();
+    let output = fixtures.wesolowski.output_element().expect("Decoding failed");
+    let proof = element_from_bytes(&fixtures.wesolowski.proof).expect("Decoding failed");
+    let trivial = vec![
+        Integer::new(),
+        Integer::from(1),
+        Integer::from(-1),
+        Integer::from(&modulus - 1u32),
+        modulus.clone(),
+        Integer::from(&modulus + 1u32),
+    ];
+    let candidates: Vec<Integer> = trivial.iter().cloned().chain([output, proof]).collect();
+    proptest!(ProptestConfig::with_cases(CASES), |(
+        forged_output in select(candidates.clone()),
+        forged_proof in select(candidates.clone()),
+        trivial_element in select(trivial.clone()),
+        in_proof in any::<bool>(),
+    )| {
+        // At least one of the two is trivial
+        let (forged_output, forged_proof) = if in_proof {
+            (forged_output, trivial_element)
+        } else {
+            (trivial_element, forged_proof)
+        };
+        let input = &fixtures.wesolowski.input;
+        prop_assert!(!fixtures.engine.verify(input, &forged_output, ITERATIONS, &forged_proof).unwrap_or(false));
+
+        // Values that fit the encoding must be rejected there as well
+        if forged_output >= 0 && forged_proof >= 0 {
+            let mut forged = fixtures.wesolowski.clone();
+            forged.output = fixtures.group.encode(&to_biguint(&forged_output)).expect("Element fits");
+            forged.proof = fixtures.group.encode(&to_biguint(&forged_proof)).expect("Element fits");
+            prop_assert!(!fixtures.accepts(&forged));
+        }
+    });
+}
+
+#[test]
+fn test_wrong_iterations_are_rejected() {
+    let fixtures = Fixtures::new();
+    proptest!(ProptestConfig::with_cases(CASES), |(scheme in scheme(), iterations in 0..4 * ITERATIONS)| {
+        prop_assume!(iterations != ITERATIONS);
+        let mut replayed = fixtures.output(scheme).clone();
+        replayed.iterations = iterations;
+        prop_assert!(!fixtures.accepts(&replayed));
+    });
+}
+
+#[test]
+fn test_pietrzak_midpoints_cannot_be_altered() {
+    let fixtures = Fixtures::new();
+    let midpoints = fixtures.pietrzak.proof.len() / ELEMENT_BYTES;
+    proptest!(ProptestConfig::with_cases(CASES), |(
+        index in 0..midpoints,
+        replacement in prop::collection::vec(any::<u8>(), ELEMENT_BYTES),
+        mode in 0..4u8,
+    )| {
+        let mut altered = fixtures.pietrzak.clone();
+        let range = index * ELEMENT_BYTES..(index + 1) * ELEMENT_BYTES;
+        let proof = &fixtures.pietrzak.proof;
+        altered.proof = match mode {
+            0 => [&proof[..range.start], &replacement[..], &proof[range.end..]].concat(),
+            1 => [&proof[..range.start], &negate(&fixtures.group, &proof[range.clone()])[..], &proof[range.end..]].concat(),
+            2 => [&proof[..range.start], &proof[range.end..]].concat(),
+            _ => [&proof[..range.start], &replacement[..], &proof[range.start..]].concat(),
+        };
+        prop_assert!(!fixtures.accepts(&altered));
+    });
+}
+
+#[test]
+fn test_batches_never_accept_altered_entries() {
+    let fixtures = Fixtures::new();
+    let outputs: Vec<VDFOutput> = (0..4)
+        .map(|action_id| fixtures.engine.compute(&input(action_id), ITERATIONS).expect("VDF computation failed"))
+        .collect();
+    proptest!(ProptestConfig::with_cases(CASES / 4), |(altered in prop::collection::vec(any::<bool>(), outputs.len()))| {
+        let batch: Vec<VDFOutput> = outputs.iter().zip(&altered)
+            .map(|(output, &altered)| {
+                let mut output = output.clone();
+                if altered {
+                    // Off by exactly -1, which a batch check in (Z/NZ)* can miss
+                    output.output = negate(&fixtures.group, &output.output);
+                }
+                output
+            })
+            .collect();
+        for (result, altered) in fixtures.engine.verify_batch(&batch).iter().zip(&altered) {
+            prop_assert_eq!(result == &Ok(true), !altered);
+        }
+    });
+}
+
+fn to_biguint(value: &Integer) -> BigUint {
+    BigUint::from_bytes_be(&value.to_digits::<u8>(rug::integer::Order::Msf))
+}